@@ -1,6 +1,7 @@
-use crate::dpll::dpll;
-use crate::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
-use crate::utils::plot_data;
+use dpll::dpll::dpll as run_dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use dpll::utils::plot_data;
 use log::{debug, error, info};
 use rayon::iter::ParallelBridge;
 use rayon::iter::ParallelIterator;
@@ -15,18 +16,20 @@ fn bench(
     path: &PathBuf,
     expected: &FormulaResultType,
     h: HeuristicType,
+    preprocess: &PreprocessConfig,
 ) -> (i32, i32, i32, Duration) {
     //info!(target: "benchmark", "Formula {:?}", path);
 
     let start = time::Instant::now();
-    let mut formula = Formula::from_file(&path).unwrap();
+    let mut formula = Formula::from_file(path).unwrap();
     formula.heuristic_type = h;
+    formula.preprocess = preprocess.clone();
 
     let timeout = Arc::new(AtomicBool::new(false));
     let timeout_copy = timeout.clone();
     let handle: JoinHandle<FormulaResultType> = thread::spawn(move || {
-        dpll::dpll(&mut formula, timeout_copy);
-        formula.result
+        run_dpll::dpll(&mut formula, timeout_copy);
+        formula.result()
     });
 
     while start.elapsed().as_secs() < 60 {
@@ -48,7 +51,7 @@ fn bench(
         Ok(result) => {
             let elapsed = start.elapsed();
             debug!(target: "benchmark", "Time: {:?}", elapsed);
-            return if result == *expected {
+            if result == *expected {
                 debug!(target: "benchmark", "Right result: {:?} Formula {:?}", result, path);
                 (1, 0, 0, elapsed)
             } else if result == FormulaResultType::Timeout {
@@ -57,16 +60,16 @@ fn bench(
             } else {
                 error!(target: "benchmark", "Wrong result: {:?} Formula {:?}", result, path);
                 (0, 0, 1, elapsed)
-            };
+            }
         } // result is the return value from dpll
         Err(e) => panic!("Thread panicked: {:?} Formula {:?}", e, path),
     }
 }
 
-pub fn benchmark() {
+pub fn benchmark(preprocess: PreprocessConfig) {
     let mut data: Vec<(HeuristicType, Vec<Duration>)> = Vec::new();
     let mut out_total_counter = 0;
-    for heuristic in vec![HeuristicType::None, HeuristicType::VSIDS] {
+    for heuristic in [HeuristicType::None, HeuristicType::VSIDS] {
         let paths = fs::read_dir("data/inputs").unwrap();
 
         let solved_counter = AtomicUsize::new(0);
@@ -89,7 +92,7 @@ pub fn benchmark() {
             fs::read_dir(dir).unwrap().par_bridge().for_each(|path| {
                 let path = path.unwrap().path();
                 total_counter.fetch_add(1, Ordering::SeqCst);
-                let (solved, timeout, error, time) = bench(&path, &expected, heuristic);
+                let (solved, timeout, error, time) = bench(&path, &expected, heuristic, &preprocess);
                 if solved == 1 {
                     let mut data = solved_times.lock().unwrap();
                     data.push(time);
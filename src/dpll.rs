@@ -1,3 +1,27 @@
+pub mod addition;
+pub mod approx;
+pub mod blocked;
+pub mod checker;
+pub mod count;
+#[allow(clippy::module_inception)]
 pub mod dpll;
+pub mod elimination;
+pub mod enumerate;
+pub mod equivalence;
+pub mod gates;
+pub mod groups;
 pub mod heuristic;
+pub mod incremental;
+pub mod local_search;
+pub mod mcs;
+pub mod mus;
+pub mod preprocess;
+pub mod probe;
+pub mod proof;
+pub mod sample;
 pub mod schemas;
+pub mod subsumption;
+pub mod transitive;
+pub mod unsat_core;
+pub mod vivify;
+pub mod xor;
@@ -0,0 +1,187 @@
+use crate::dpll::schemas::{Clause, ClauseType, Formula};
+use log::debug;
+use std::collections::{BinaryHeap, HashMap};
+
+// the number of literals that are visited at most to find patterns
+const EFFORT: usize = 50_000_000;
+
+// the literals of a clause are marked by their position in a vector over all literals
+fn mark(literal: i16) -> usize {
+    2 * (literal.unsigned_abs() - 1) as usize + usize::from(literal < 0)
+}
+
+// the number of clauses that are saved by replacing a pattern with a new variable
+fn reduction(literals: usize, clauses: usize) -> isize {
+    (literals * clauses) as isize - literals as isize - clauses as isize
+}
+
+/// A pattern of clauses that can be replaced: every row holds the clauses that only differ in the
+/// literal of the same position, together they are the product of the literals and the rest of
+/// the clauses.
+struct Pattern {
+    literals: Vec<i16>,
+    rows: Vec<Vec<usize>>,
+}
+
+impl Formula {
+    /// Bounded variable addition in the style of SimpleBVA.
+    ///
+    /// If every literal `l` of a set meets every rest `R` of a set in a clause `l ∨ R`, these
+    /// clauses are replaced by `x ∨ l` for every literal and `¬x ∨ R` for every rest with a new
+    /// variable `x`, as long as this removes clauses. This turns the pairwise at-most-one
+    /// encodings into logarithmic ones. The literals with the most occurrences are tried first.
+    /// Resolving the new clauses on `x` gives back the replaced ones, so the model of the original
+    /// variables needs no reconstruction and the new variables are left out of it.
+    /// Only original clauses without frozen variables are replaced.
+    /// Returns the number of added variables.
+    pub(crate) fn add_variables(&mut self) -> usize {
+        let mut marks = vec![false; 2 * self.variables.len()];
+        let mut effort = EFFORT;
+        let mut added = 0;
+        let mut queue: BinaryHeap<(usize, i16)> = (0..self.variables.len())
+            .filter(|variable_index| !self.is_frozen(*variable_index))
+            .flat_map(|variable_index| {
+                let literal = (variable_index + 1) as i16;
+                [literal, -literal]
+            })
+            .map(|literal| (self.replaceable_occurrences(literal).len(), literal))
+            .filter(|(occurrences, _)| *occurrences > 1)
+            .collect();
+        while let Some((occurrences, literal)) = queue.pop() {
+            if effort == 0 || self.variables.len() >= i16::MAX as usize {
+                break;
+            }
+            // the occurrences change with every replacement, the literal is tried again with the new ones
+            let current = self.replaceable_occurrences(literal).len();
+            if current != occurrences {
+                if current > 1 {
+                    queue.push((current, literal));
+                }
+                continue;
+            }
+            let Some(pattern) = self.find_pattern(literal, &mut marks, &mut effort) else {
+                continue;
+            };
+            let variable = self.replace_pattern(&pattern);
+            added += 1;
+            marks.resize(2 * self.variables.len(), false);
+            for lit in pattern.literals.iter().chain([variable, -variable].iter()) {
+                queue.push((self.replaceable_occurrences(*lit).len(), *lit));
+            }
+        }
+        added
+    }
+
+    /// The original clauses with the literal that contain no frozen variable.
+    fn replaceable_occurrences(&self, literal: i16) -> Vec<usize> {
+        self.original_occurrences(literal)
+            .into_iter()
+            .filter(|clause_index| {
+                let literals = &self.clauses[*clause_index].literals;
+                literals.len() > 1 && !literals.iter().any(|lit| self.is_frozen((lit.unsigned_abs() - 1) as usize))
+            })
+            .collect()
+    }
+
+    /// Grow the pattern of the literal by the literal that matches the most of its clauses, as
+    /// long as the reduction increases.
+    fn find_pattern(&self, literal: i16, marks: &mut [bool], effort: &mut usize) -> Option<Pattern> {
+        let mut pattern = Pattern {
+            literals: vec![literal],
+            rows: self.replaceable_occurrences(literal).into_iter().map(|clause_index| vec![clause_index]).collect(),
+        };
+        loop {
+            // the rows that have a clause with the rest of their first clause for every other literal
+            let mut matches: HashMap<i16, Vec<(usize, usize)>> = HashMap::new();
+            for (row_index, row) in pattern.rows.iter().enumerate() {
+                let clause = &self.clauses[row[0]].literals;
+                let rest: Vec<i16> = clause.iter().copied().filter(|lit| *lit != literal).collect();
+                for lit in &rest {
+                    marks[mark(*lit)] = true;
+                }
+                // the clauses with the rest contain its literal with the fewest occurrences
+                let rarest = *rest
+                    .iter()
+                    .min_by_key(|lit| {
+                        let variable = &self.variables[(lit.unsigned_abs() - 1) as usize];
+                        if **lit > 0 { variable.positive_occurrences.len() } else { variable.negative_occurrences.len() }
+                    })
+                    .unwrap();
+                for other in self.replaceable_occurrences(rarest) {
+                    let other_literals = &self.clauses[other].literals;
+                    if other_literals.len() != clause.len() {
+                        continue;
+                    }
+                    *effort = effort.saturating_sub(other_literals.len());
+                    let mut unmarked = other_literals.iter().filter(|lit| !marks[mark(**lit)]);
+                    let (Some(candidate), None) = (unmarked.next(), unmarked.next()) else {
+                        continue;
+                    };
+                    if pattern.literals.contains(candidate) || pattern.literals.contains(&-candidate) {
+                        continue;
+                    }
+                    let found = matches.entry(*candidate).or_default();
+                    if found.last().is_none_or(|(last, _)| *last != row_index) {
+                        found.push((row_index, other));
+                    }
+                }
+                for lit in &rest {
+                    marks[mark(*lit)] = false;
+                }
+                if *effort == 0 {
+                    break;
+                }
+            }
+            let best = matches.into_iter().max_by_key(|(lit, found)| (found.len(), -lit.abs(), *lit));
+            let Some((next, found)) = best else {
+                break;
+            };
+            if reduction(pattern.literals.len() + 1, found.len()) <= reduction(pattern.literals.len(), pattern.rows.len()) {
+                break;
+            }
+            pattern.rows = found
+                .into_iter()
+                .map(|(row_index, other)| {
+                    let mut row = pattern.rows[row_index].clone();
+                    row.push(other);
+                    row
+                })
+                .collect();
+            pattern.literals.push(next);
+        }
+        (reduction(pattern.literals.len(), pattern.rows.len()) > 0).then_some(pattern)
+    }
+
+    /// Replace the clauses of the pattern with the clauses of a new variable, which is returned.
+    /// The clauses of the new variable are RAT on it: nothing contains its negation when `x ∨ l`
+    /// is added, and the resolvents of `¬x ∨ R` with them are the replaced clauses.
+    fn replace_pattern(&mut self, pattern: &Pattern) -> i16 {
+        let variable = self.new_internal_variable();
+        debug!(target: "add_variables", "replace {} clauses of {:?} with {} by {} clauses", pattern.literals.len() * pattern.rows.len(), &pattern.literals, variable, pattern.literals.len() + pattern.rows.len());
+        let mut definitions = Vec::new();
+        for (column, lit) in pattern.literals.iter().enumerate() {
+            let id = Clause::id(self.clauses.len());
+            definitions.push(id);
+            self.proof_add(id, &[variable, *lit], &[]);
+            // the definition is not implied, but together with the rows it replaces the clauses of its column
+            let antecedents: Vec<usize> = pattern.rows.iter().map(|row| Clause::id(row[column])).collect();
+            self.record_antecedents(id, &[variable, *lit], &antecedents);
+            self.insert_clause(vec![variable, *lit], ClauseType::Derived);
+        }
+        for row in &pattern.rows {
+            let mut literals = vec![-variable];
+            literals.extend(self.clauses[row[0]].literals.iter().copied().filter(|lit| *lit != pattern.literals[0]));
+            let candidates: Vec<(usize, Vec<usize>)> = definitions
+                .iter()
+                .zip(row.iter())
+                .map(|(definition, clause_index)| (*definition, vec![Clause::id(*clause_index)]))
+                .collect();
+            self.proof_add_rat(Clause::id(self.clauses.len()), &literals, &candidates);
+            self.insert_clause(literals, ClauseType::Derived);
+        }
+        for clause_index in pattern.rows.iter().flatten() {
+            self.remove_clause(*clause_index);
+        }
+        variable
+    }
+}
@@ -0,0 +1,154 @@
+use crate::dpll::enumerate::ModelEnumerator;
+use crate::dpll::schemas::Formula;
+use log::{debug, info};
+use num_bigint::BigUint;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+// the variables of an XOR constraint and its parity, true if an odd number of them is true
+pub(crate) type Xor = (Vec<i16>, bool);
+
+/// Approximate model counter in the style of ApproxMC.
+///
+/// Random XOR constraints over the sampling set split the models into cells of about equal size.
+/// The counter searches the number of XORs m where a cell has less than `threshold` models, then
+/// the number of models in the cell times 2^m is an estimate of the count. The median of the
+/// estimates of several independent hashes is within a factor of (1 + epsilon) of the exact count
+/// with a probability of at least 1 - delta.
+#[derive(Debug)]
+pub struct ApproxCounter {
+    clauses: Vec<Vec<i16>>,
+    num_variables: usize,
+    // the variables that are counted, starting with 1
+    sampling_set: Vec<i16>,
+    threshold: usize,
+    iterations: usize,
+    rng: StdRng,
+    pub cell_queries: usize,
+}
+
+impl ApproxCounter {
+    /// Count the models projected onto the given variables, without projection all variables of
+    /// the formula are used. The random hashes are chosen by the seed.
+    /// Returns an error if epsilon is not positive or delta is not between 0 and 1.
+    pub fn new(
+        num_variables: usize,
+        clauses: Vec<Vec<i16>>,
+        projection: Option<Vec<i16>>,
+        epsilon: f64,
+        delta: f64,
+        seed: u64,
+    ) -> Result<Self, String> {
+        if epsilon.is_nan() || epsilon <= 0.0 {
+            return Err(format!("epsilon has to be positive: {}", epsilon));
+        }
+        if delta.is_nan() || delta <= 0.0 || delta >= 1.0 {
+            return Err(format!("delta has to be between 0 and 1: {}", delta));
+        }
+        let sampling_set = projection.unwrap_or_else(|| (1..=num_variables as i16).collect());
+        let threshold = 1.0 + 9.84 * (1.0 + epsilon / (1.0 + epsilon)) * (1.0 + 1.0 / epsilon).powi(2);
+        let iterations = (17.0 * (3.0 / delta).log2()).ceil();
+        Ok(Self {
+            clauses,
+            num_variables,
+            sampling_set,
+            threshold: threshold.ceil() as usize,
+            iterations: iterations as usize,
+            rng: StdRng::seed_from_u64(seed),
+            cell_queries: 0,
+        })
+    }
+
+    /// The estimated number of models. A formula with less models than the threshold is counted exactly.
+    pub fn count(&mut self) -> BigUint {
+        let models = self.count_cell(&[]);
+        if models < self.threshold {
+            info!(target: "approx_count", "counted {} models exactly", models);
+            return BigUint::from(models);
+        }
+        info!(target: "approx_count", "threshold {}, {} iterations", self.threshold, self.iterations);
+        let mut estimates = Vec::with_capacity(self.iterations);
+        for iteration in 0..self.iterations {
+            let hash = random_hash(&mut self.rng, &self.sampling_set, self.sampling_set.len());
+            let (xors, models) = self.search_cell(&hash);
+            debug!(target: "approx_count", "iteration {}: {} models with {} xors", iteration, models, xors);
+            estimates.push(BigUint::from(models) << xors);
+        }
+        estimates.sort();
+        estimates.swap_remove(estimates.len() / 2)
+    }
+
+    /// Binary search for the smallest number of XORs of the hash whose cell has less models than
+    /// the threshold. The cell without XORs is known to be larger.
+    /// Returns the number of XORs and the models in their cell.
+    fn search_cell(&mut self, hash: &[Xor]) -> (usize, usize) {
+        let mut models_by_xors: HashMap<usize, usize> = HashMap::new();
+        let mut low = 0;
+        let mut high = hash.len();
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            let models = self.count_cell(&hash[..middle]);
+            models_by_xors.insert(middle, models);
+            if models < self.threshold {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        let models = match models_by_xors.get(&high) {
+            Some(models) => *models,
+            None => self.count_cell(&hash[..high]),
+        };
+        (high, models)
+    }
+
+    /// The number of models that satisfy the XORs, up to the threshold.
+    fn count_cell(&mut self, xors: &[Xor]) -> usize {
+        self.cell_queries += 1;
+        cell_models(self.num_variables, &self.clauses, &self.sampling_set, xors, self.threshold).len()
+    }
+}
+
+/// Random XORs over the sampling set. Every XOR contains each variable with probability 1/2 and
+/// has a random parity.
+pub(crate) fn random_hash(rng: &mut StdRng, sampling_set: &[i16], xors: usize) -> Vec<Xor> {
+    (0..xors)
+        .map(|_| {
+            let variables = sampling_set.iter().copied().filter(|_| rng.gen::<bool>()).collect();
+            (variables, rng.gen::<bool>())
+        })
+        .collect()
+}
+
+/// Up to `limit` models of the clauses that satisfy the XORs, projected onto the sampling set.
+/// The formula is built fresh for every cell with the XORs as native constraints.
+pub(crate) fn cell_models(
+    num_variables: usize,
+    clauses: &[Vec<i16>],
+    sampling_set: &[i16],
+    xors: &[Xor],
+    limit: usize,
+) -> Vec<Vec<i16>> {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    for (variables, parity) in xors {
+        // the native constraints are true if an odd number of literals is true, so a negated
+        // literal makes an even parity, an empty XOR with even parity is always satisfied
+        let mut literals = variables.clone();
+        if !parity {
+            match literals.first_mut() {
+                Some(first) => *first = -*first,
+                None => continue,
+            }
+        }
+        formula.add_xor_constraint(literals);
+    }
+    let enumerator = ModelEnumerator::new(&mut formula, Some(sampling_set.to_vec()), Arc::new(AtomicBool::new(false)))
+        .expect("The sampling set contains only variables of the formula");
+    enumerator.take(limit).collect()
+}
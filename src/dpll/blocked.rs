@@ -0,0 +1,156 @@
+use crate::dpll::schemas::Formula;
+use log::debug;
+
+// the number of literals that are visited at most to find blocked and covered clauses
+const EFFORT: usize = 50_000_000;
+// covered clauses are not extended beyond this length
+const COVERED_LENGTH: usize = 100;
+
+// the literals of a clause are marked by their position in a vector over all literals
+fn mark(literal: i16) -> usize {
+    2 * (literal.unsigned_abs() - 1) as usize + usize::from(literal < 0)
+}
+
+/// The outcome of the covered literal addition for a clause.
+enum Cover {
+    Blocked(i16),
+    Tautology,
+    Kept,
+}
+
+impl Formula {
+    /// Blocked clause elimination, and covered clause elimination if `covered` is enabled.
+    ///
+    /// A clause is blocked on one of its literals if all resolvents with the clauses that contain
+    /// the negated literal are tautologies. Blocked clauses are removed and pushed to the
+    /// reconstruction stack with the blocking literal as witness, setting it true satisfies the
+    /// clause without falsifying any other one.
+    /// Covered clause elimination extends a clause by covered literals first: a literal is covered
+    /// if it is in all clauses that have no tautological resolvent with the clause on a literal.
+    /// The clause is removed if the extended clause is blocked or a tautology, every extension is
+    /// pushed to the reconstruction stack with the literal it was covered on.
+    /// Literals of frozen variables never block a clause.
+    /// Returns the number of removed clauses.
+    pub(crate) fn eliminate_blocked_clauses(&mut self) -> usize {
+        let mut marks = vec![false; 2 * self.variables.len()];
+        let mut effort = EFFORT;
+        let mut removed = 0;
+        let mut queued = vec![false; self.clauses.len()];
+        let mut queue: Vec<usize> = (0..self.clauses.len()).filter(|clause_index| !self.clauses[*clause_index].deleted).collect();
+        for clause_index in &queue {
+            queued[*clause_index] = true;
+        }
+        queue.reverse();
+        while let Some(clause_index) = queue.pop() {
+            queued[clause_index] = false;
+            if self.clauses[clause_index].deleted {
+                continue;
+            }
+            let literals = self.clauses[clause_index].literals.clone();
+            let (cover, steps) = self.cover(&literals, &mut marks, &mut effort);
+            if matches!(cover, Cover::Kept) {
+                if effort == 0 {
+                    break;
+                }
+                continue;
+            }
+            debug!(target: "eliminate_blocked_clauses", "remove {:?} with {} covered literals", &literals, steps.last().map_or(literals.len(), |(_, extended)| extended.len()) - literals.len());
+            for (witness, extended) in steps {
+                self.push_reconstruction(witness, extended);
+            }
+            self.remove_clause(clause_index);
+            removed += 1;
+            // the clauses with the negated literals may be blocked now
+            for lit in literals {
+                let variable = &self.variables[(lit.unsigned_abs() - 1) as usize];
+                let occurrences = if lit > 0 { &variable.negative_occurrences } else { &variable.positive_occurrences };
+                for other in occurrences {
+                    if !queued[*other] {
+                        queued[*other] = true;
+                        queue.push(*other);
+                    }
+                }
+            }
+        }
+        removed
+    }
+
+    /// Check if the clause is blocked, with covered clause elimination after extending it by
+    /// covered literals. Returns the reconstruction steps, the witness of each step and the clause
+    /// it makes true, in the order they are pushed.
+    fn cover(&self, literals: &[i16], marks: &mut [bool], effort: &mut usize) -> (Cover, Vec<(i16, Vec<i16>)>) {
+        let mut extended = literals.to_vec();
+        for lit in &extended {
+            marks[mark(*lit)] = true;
+        }
+        let mut steps = Vec::new();
+        let mut cover = Cover::Kept;
+        // with covered literals every literal is tried again after an extension
+        let mut pivot = 0;
+        let mut unchanged = 0;
+        while unchanged < extended.len() && *effort > 0 {
+            let literal = extended[pivot % extended.len()];
+            pivot += 1;
+            unchanged += 1;
+            if self.is_frozen((literal.unsigned_abs() - 1) as usize) {
+                continue;
+            }
+            let variable = &self.variables[(literal.unsigned_abs() - 1) as usize];
+            let occurrences = if literal > 0 { &variable.negative_occurrences } else { &variable.positive_occurrences };
+            // the literals that are in all clauses with a non tautological resolvent
+            let mut intersection: Option<Vec<i16>> = None;
+            for other in occurrences {
+                let other = &self.clauses[*other];
+                if other.deleted {
+                    continue;
+                }
+                *effort = effort.saturating_sub(other.literals.len());
+                if other.literals.iter().any(|lit| *lit != -literal && marks[mark(-lit)]) {
+                    continue;
+                }
+                if !self.preprocess.covered {
+                    intersection = Some(Vec::new());
+                    break;
+                }
+                match &mut intersection {
+                    Some(intersection) => intersection.retain(|lit| other.literals.contains(lit)),
+                    None => intersection = Some(other.literals.iter().copied().filter(|lit| *lit != -literal && !marks[mark(*lit)]).collect()),
+                }
+                if intersection.as_ref().is_some_and(|intersection| intersection.is_empty()) {
+                    break;
+                }
+            }
+            match intersection {
+                None => {
+                    cover = Cover::Blocked(literal);
+                    break;
+                }
+                Some(intersection) if intersection.is_empty() => {}
+                Some(intersection) => {
+                    steps.push((literal, extended.clone()));
+                    if intersection.iter().any(|lit| marks[mark(-lit)]) {
+                        cover = Cover::Tautology;
+                        break;
+                    }
+                    for lit in intersection {
+                        marks[mark(lit)] = true;
+                        extended.push(lit);
+                    }
+                    unchanged = 0;
+                    if extended.len() > COVERED_LENGTH {
+                        break;
+                    }
+                }
+            }
+        }
+        for lit in &extended {
+            marks[mark(*lit)] = false;
+        }
+        match cover {
+            Cover::Blocked(literal) => steps.push((literal, extended)),
+            Cover::Tautology => {}
+            Cover::Kept => steps.clear(),
+        }
+        (cover, steps)
+    }
+}
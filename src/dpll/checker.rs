@@ -0,0 +1,847 @@
+use crate::dpll::proof::ProofFormat;
+use crate::utils::write_numbered_clauses;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// One line of a DRAT or LRAT proof.
+///
+/// DRAT proofs only contain literals, the ids and hints are only set for LRAT proofs.
+#[derive(Debug, Clone)]
+pub enum ProofStep {
+    Add {
+        id: usize,
+        literals: Vec<i32>,
+        hints: Vec<i64>,
+    },
+    Delete {
+        ids: Vec<usize>,
+        literals: Vec<i32>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CheckResult {
+    Verified,
+    /// The first lemma that can not be derived, counted from 1 in the order of the proof.
+    Invalid {
+        lemma: usize,
+        literals: Vec<i32>,
+    },
+    /// All lemmas are valid, but the proof does not refute the formula.
+    NoConflict,
+}
+
+/// A hint of a lemma: a clause that becomes unit or falsified, or a candidate clause of a RAT check.
+#[derive(Debug, Clone, Copy)]
+enum Hint {
+    Unit(usize),
+    Rat(usize),
+}
+
+#[derive(Debug)]
+struct CheckerClause {
+    literals: Vec<i32>,
+    // the first literal of the lemma, the watches change the order of the literals
+    pivot: Option<i32>,
+    active: bool,
+    // the clause is needed to derive the empty clause
+    core: bool,
+    // position of the lemma in the proof, 0 for original clauses
+    lemma: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Applied {
+    Add(usize),
+    Delete(usize),
+}
+
+/// Read the clauses of a DIMACS CNF file with their DIMACS order.
+pub fn read_cnf(path: &PathBuf) -> Result<Vec<Vec<i32>>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("can not read {:?}: {}", path, e))?;
+    let mut clauses = Vec::new();
+    let mut current = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('c') || line.starts_with('p') || line.starts_with('%') {
+            continue;
+        }
+        for part in line.split_whitespace() {
+            let lit = part
+                .parse::<i32>()
+                .map_err(|_| format!("invalid literal: {}", part))?;
+            if lit == 0 {
+                clauses.push(std::mem::take(&mut current));
+            } else {
+                current.push(lit);
+            }
+        }
+    }
+    Ok(clauses)
+}
+
+/// Guess the format of a proof: binary proofs end every clause with a zero byte, which a text proof
+/// does not contain, not even in its comments. Text LRAT lines start with a clause id and have a
+/// second zero that ends the hints.
+/// Binary LRAT proofs can not be told apart from binary DRAT proofs.
+fn detect_format(data: &[u8]) -> ProofFormat {
+    if data.contains(&0) {
+        return ProofFormat::Binary;
+    }
+    let text = String::from_utf8_lossy(data);
+    for line in text.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() || parts[0] == "c" {
+            continue;
+        }
+        if parts.len() > 1 && parts[1] == "d" || parts.iter().filter(|p| **p == "0").count() > 1 {
+            return ProofFormat::Lrat;
+        }
+        return ProofFormat::Text;
+    }
+    ProofFormat::Text
+}
+
+fn read_binary_number(data: &[u8], position: &mut usize) -> Result<usize, String> {
+    let mut number = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = *data
+            .get(*position)
+            .ok_or_else(|| "unexpected end of binary proof".to_string())?;
+        *position += 1;
+        number |= ((byte & 127) as usize) << shift;
+        if byte < 128 {
+            return Ok(number);
+        }
+        shift += 7;
+    }
+}
+
+fn decode_literal(number: usize) -> i32 {
+    let variable = (number >> 1) as i32;
+    if number & 1 == 1 {
+        -variable
+    } else {
+        variable
+    }
+}
+
+/// Read all binary numbers until the terminating zero.
+fn read_binary_list(data: &[u8], position: &mut usize) -> Result<Vec<usize>, String> {
+    let mut list = Vec::new();
+    loop {
+        match read_binary_number(data, position)? {
+            0 => return Ok(list),
+            number => list.push(number),
+        }
+    }
+}
+
+fn parse_numbers<T: std::str::FromStr>(parts: &[&str]) -> Result<Vec<T>, String> {
+    parts
+        .iter()
+        .map(|p| p.parse::<T>().map_err(|_| format!("invalid number: {}", p)))
+        .collect()
+}
+
+/// Read a DRAT or LRAT proof, without a format the format is detected from the content.
+pub fn read_proof(
+    path: &PathBuf,
+    format: Option<ProofFormat>,
+) -> Result<(ProofFormat, Vec<ProofStep>), String> {
+    let data = fs::read(path).map_err(|e| format!("can not read {:?}: {}", path, e))?;
+    let format = format.unwrap_or_else(|| detect_format(&data));
+    debug!(target: "read_proof", "proof format: {:?}", format);
+    let mut steps = Vec::new();
+    match format {
+        ProofFormat::Binary | ProofFormat::LratBinary => {
+            let mut position = 0;
+            while position < data.len() {
+                let kind = data[position];
+                position += 1;
+                match (kind, format) {
+                    (b'a', ProofFormat::Binary) => steps.push(ProofStep::Add {
+                        id: 0,
+                        literals: read_binary_list(&data, &mut position)?
+                            .into_iter()
+                            .map(decode_literal)
+                            .collect(),
+                        hints: Vec::new(),
+                    }),
+                    (b'a', _) => {
+                        let id = read_binary_number(&data, &mut position)? >> 1;
+                        let literals = read_binary_list(&data, &mut position)?
+                            .into_iter()
+                            .map(decode_literal)
+                            .collect();
+                        let hints = read_binary_list(&data, &mut position)?
+                            .into_iter()
+                            .map(|h| decode_literal(h) as i64)
+                            .collect();
+                        steps.push(ProofStep::Add { id, literals, hints });
+                    }
+                    (b'd', ProofFormat::Binary) => steps.push(ProofStep::Delete {
+                        ids: Vec::new(),
+                        literals: read_binary_list(&data, &mut position)?
+                            .into_iter()
+                            .map(decode_literal)
+                            .collect(),
+                    }),
+                    (b'd', _) => steps.push(ProofStep::Delete {
+                        ids: read_binary_list(&data, &mut position)?
+                            .into_iter()
+                            .map(|id| id >> 1)
+                            .collect(),
+                        literals: Vec::new(),
+                    }),
+                    // some tools end binary proofs with line breaks
+                    (b'\n' | b'\r', _) => {}
+                    _ => return Err(format!("invalid byte {} in binary proof at {}", kind, position - 1)),
+                }
+            }
+        }
+        ProofFormat::Text | ProofFormat::Lrat => {
+            let text = String::from_utf8_lossy(&data);
+            for line in text.lines() {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.is_empty() || parts[0].starts_with('c') {
+                    continue;
+                }
+                if format == ProofFormat::Text {
+                    let deletion = parts[0] == "d";
+                    let parts = if deletion { &parts[1..] } else { &parts[..] };
+                    let mut literals: Vec<i32> = parse_numbers(parts)?;
+                    literals.pop_if(|lit| *lit == 0);
+                    steps.push(if deletion {
+                        ProofStep::Delete { ids: Vec::new(), literals }
+                    } else {
+                        ProofStep::Add { id: 0, literals, hints: Vec::new() }
+                    });
+                } else if parts.len() > 1 && parts[1] == "d" {
+                    let mut ids: Vec<usize> = parse_numbers(&parts[2..])?;
+                    ids.pop_if(|id| *id == 0);
+                    steps.push(ProofStep::Delete { ids, literals: Vec::new() });
+                } else {
+                    let numbers: Vec<i64> = parse_numbers(&parts)?;
+                    let end = numbers[1..]
+                        .iter()
+                        .position(|n| *n == 0)
+                        .ok_or_else(|| format!("missing zero in lrat line: {}", line))?
+                        + 1;
+                    let mut hints = numbers[end + 1..].to_vec();
+                    hints.pop_if(|hint| *hint == 0);
+                    steps.push(ProofStep::Add {
+                        id: numbers[0] as usize,
+                        literals: numbers[1..end].iter().map(|lit| *lit as i32).collect(),
+                        hints,
+                    });
+                }
+            }
+        }
+    }
+    Ok((format, steps))
+}
+
+fn variable(literal: i32) -> usize {
+    (literal.unsigned_abs() - 1) as usize
+}
+
+fn literal_code(literal: i32) -> usize {
+    2 * variable(literal) + (literal < 0) as usize
+}
+
+/// A proof checker for DRAT and LRAT proofs.
+///
+/// DRAT proofs are checked backwards: first all lemmas are added until the empty clause, then
+/// only the lemmas that are needed for the final conflict are checked, starting with the last one.
+/// Unit propagation prefers clauses that are already part of the core, so the core stays small.
+/// LRAT proofs are checked forward by following the hints of every lemma.
+#[derive(Debug)]
+pub struct ProofChecker {
+    clauses: Vec<CheckerClause>,
+    num_original: usize,
+    // clause indexes by the code of their two watched literals
+    watches: Vec<Vec<usize>>,
+    // 1 for true, -1 for false and 0 for free variables
+    values: Vec<i8>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<i32>,
+    units: Vec<usize>,
+    // active clauses by their sorted literals, used to find deleted clauses
+    lookup: HashMap<Vec<i32>, Vec<usize>>,
+    hints: Vec<Vec<Hint>>,
+    applied: Vec<Applied>,
+    final_hints: Vec<Hint>,
+    // LRAT: clause ids of the proof and the original ids used by the lemmas
+    lrat_hints: HashMap<usize, Vec<i64>>,
+    empty_clause_id: Option<usize>,
+}
+
+impl ProofChecker {
+    pub fn new(clauses: Vec<Vec<i32>>) -> Self {
+        let mut checker = Self {
+            clauses: Vec::new(),
+            num_original: clauses.len(),
+            watches: Vec::new(),
+            values: Vec::new(),
+            reasons: Vec::new(),
+            trail: Vec::new(),
+            units: Vec::new(),
+            lookup: HashMap::new(),
+            hints: Vec::new(),
+            applied: Vec::new(),
+            final_hints: Vec::new(),
+            lrat_hints: HashMap::new(),
+            empty_clause_id: None,
+        };
+        for clause in clauses {
+            checker.add_clause(clause, 0);
+        }
+        checker
+    }
+
+    fn value(&self, literal: i32) -> i8 {
+        let value = self.values[variable(literal)];
+        if literal > 0 {
+            value
+        } else {
+            -value
+        }
+    }
+
+    fn ensure_variable(&mut self, literal: i32) {
+        let variable = variable(literal);
+        if variable >= self.values.len() {
+            self.values.resize(variable + 1, 0);
+            self.reasons.resize(variable + 1, None);
+            self.watches.resize(2 * (variable + 1), Vec::new());
+        }
+    }
+
+    fn add_clause(&mut self, literals: Vec<i32>, lemma: usize) -> usize {
+        let mut clause: Vec<i32> = Vec::with_capacity(literals.len());
+        for lit in literals {
+            if !clause.contains(&lit) {
+                self.ensure_variable(lit);
+                clause.push(lit);
+            }
+        }
+        let index = self.clauses.len();
+        match clause.len() {
+            0 => {}
+            1 => self.units.push(index),
+            _ => {
+                self.watches[literal_code(clause[0])].push(index);
+                self.watches[literal_code(clause[1])].push(index);
+            }
+        }
+        let mut key = clause.clone();
+        key.sort_unstable();
+        self.lookup.entry(key).or_default().push(index);
+        self.clauses.push(CheckerClause {
+            pivot: clause.first().copied(),
+            literals: clause,
+            active: true,
+            core: false,
+            lemma,
+        });
+        self.hints.push(Vec::new());
+        index
+    }
+
+    /// Deactivate the active clause with the given literals, the watches are kept so the clause
+    /// can be activated again when the deletion is undone in the backward check.
+    fn delete_clause(&mut self, literals: &[i32]) -> Option<usize> {
+        let mut key = literals.to_vec();
+        key.sort_unstable();
+        key.dedup();
+        let index = self.lookup.get_mut(&key)?.pop()?;
+        self.clauses[index].active = false;
+        Some(index)
+    }
+
+    fn assign(&mut self, literal: i32, reason: Option<usize>) {
+        self.values[variable(literal)] = if literal > 0 { 1 } else { -1 };
+        self.reasons[variable(literal)] = reason;
+        self.trail.push(literal);
+    }
+
+    fn unassign_all(&mut self) {
+        for literal in self.trail.drain(..) {
+            self.values[variable(literal)] = 0;
+            self.reasons[variable(literal)] = None;
+        }
+    }
+
+    /// Propagate the negation of the given literal in all active clauses that are core or not core.
+    fn propagate_literal(&mut self, literal: i32, core: bool) -> Option<usize> {
+        let false_literal = -literal;
+        let code = literal_code(false_literal);
+        let mut watch_list = std::mem::take(&mut self.watches[code]);
+        let mut conflict = None;
+        let mut i = 0;
+        let mut j = 0;
+        while i < watch_list.len() {
+            let clause_index = watch_list[i];
+            i += 1;
+            if !self.clauses[clause_index].active || self.clauses[clause_index].core != core {
+                watch_list[j] = clause_index;
+                j += 1;
+                continue;
+            }
+            if self.clauses[clause_index].literals[0] == false_literal {
+                self.clauses[clause_index].literals.swap(0, 1);
+            }
+            let first = self.clauses[clause_index].literals[0];
+            if self.value(first) == 1 {
+                watch_list[j] = clause_index;
+                j += 1;
+                continue;
+            }
+            let new_watch = (2..self.clauses[clause_index].literals.len())
+                .find(|k| self.value(self.clauses[clause_index].literals[*k]) != -1);
+            if let Some(k) = new_watch {
+                self.clauses[clause_index].literals.swap(1, k);
+                let new_literal = self.clauses[clause_index].literals[1];
+                self.watches[literal_code(new_literal)].push(clause_index);
+                continue;
+            }
+            watch_list[j] = clause_index;
+            j += 1;
+            if self.value(first) == -1 {
+                conflict = Some(clause_index);
+                while i < watch_list.len() {
+                    watch_list[j] = watch_list[i];
+                    i += 1;
+                    j += 1;
+                }
+                break;
+            }
+            self.assign(first, Some(clause_index));
+        }
+        watch_list.truncate(j);
+        self.watches[code] = watch_list;
+        conflict
+    }
+
+    /// Unit propagation with core first: all core clauses are propagated until nothing changes,
+    /// only then one literal is propagated in the other clauses.
+    fn propagate(&mut self) -> Option<usize> {
+        let mut core_head = 0;
+        let mut all_head = 0;
+        loop {
+            while core_head < self.trail.len() {
+                let literal = self.trail[core_head];
+                core_head += 1;
+                if let Some(conflict) = self.propagate_literal(literal, true) {
+                    return Some(conflict);
+                }
+            }
+            if all_head == self.trail.len() {
+                return None;
+            }
+            let literal = self.trail[all_head];
+            all_head += 1;
+            if let Some(conflict) = self.propagate_literal(literal, false) {
+                return Some(conflict);
+            }
+        }
+    }
+
+    /// Mark the clauses of the conflict as core and return them in the order of the trail.
+    fn analyse(&mut self, conflict: usize) -> Vec<Hint> {
+        let mut seen = vec![false; self.values.len()];
+        self.clauses[conflict].core = true;
+        for lit in &self.clauses[conflict].literals {
+            seen[variable(*lit)] = true;
+        }
+        let mut used = Vec::new();
+        for literal in self.trail.iter().rev() {
+            if !seen[variable(*literal)] {
+                continue;
+            }
+            if let Some(reason) = self.reasons[variable(*literal)] {
+                self.clauses[reason].core = true;
+                used.push(Hint::Unit(reason));
+                for lit in &self.clauses[reason].literals {
+                    seen[variable(*lit)] = true;
+                }
+            }
+        }
+        used.reverse();
+        used.push(Hint::Unit(conflict));
+        used
+    }
+
+    /// Reverse unit propagation: the negation of the clause has to propagate to a conflict.
+    fn rup(&mut self, literals: &[i32]) -> Option<Vec<Hint>> {
+        self.unassign_all();
+        for lit in literals {
+            self.ensure_variable(*lit);
+            match self.value(*lit) {
+                // the clause contains a literal and its negation
+                1 => return Some(Vec::new()),
+                -1 => {}
+                _ => self.assign(-lit, None),
+            }
+        }
+        let mut conflict = None;
+        for unit in self.units.clone() {
+            if !self.clauses[unit].active {
+                continue;
+            }
+            let literal = self.clauses[unit].literals[0];
+            match self.value(literal) {
+                1 => {}
+                -1 => {
+                    conflict = Some(unit);
+                    break;
+                }
+                _ => self.assign(literal, Some(unit)),
+            }
+        }
+        if conflict.is_none() {
+            conflict = self
+                .clauses
+                .iter()
+                .position(|clause| clause.active && clause.literals.is_empty());
+        }
+        if conflict.is_none() {
+            conflict = self.propagate();
+        }
+        let hints = conflict.map(|conflict| self.analyse(conflict));
+        self.unassign_all();
+        hints
+    }
+
+    /// Resolution asymmetric tautology on the first literal: every resolvent with a clause that
+    /// contains the negated pivot has to be a tautology or has to be derived by unit propagation.
+    fn rat(&mut self, pivot: Option<i32>, literals: &[i32]) -> Option<Vec<Hint>> {
+        let pivot = pivot?;
+        let candidates: Vec<usize> = (0..self.clauses.len())
+            .filter(|index| {
+                self.clauses[*index].active && self.clauses[*index].literals.contains(&-pivot)
+            })
+            .collect();
+        let mut hints = Vec::new();
+        for candidate in candidates {
+            let mut resolvent = literals.to_vec();
+            let mut tautology = false;
+            for lit in &self.clauses[candidate].literals {
+                if *lit == -pivot {
+                    continue;
+                }
+                if literals.contains(&-lit) {
+                    tautology = true;
+                    break;
+                }
+                resolvent.push(*lit);
+            }
+            if tautology {
+                continue;
+            }
+            let used = self.rup(&resolvent)?;
+            self.clauses[candidate].core = true;
+            hints.push(Hint::Rat(candidate));
+            hints.extend(used);
+        }
+        Some(hints)
+    }
+
+    /// Check a DRAT proof backwards.
+    pub fn check_drat(&mut self, steps: &[ProofStep]) -> CheckResult {
+        let mut lemma = 0;
+        let mut empty_clause = None;
+        for step in steps {
+            match step {
+                ProofStep::Add { literals, .. } => {
+                    lemma += 1;
+                    let index = self.add_clause(literals.clone(), lemma);
+                    self.applied.push(Applied::Add(index));
+                    if literals.is_empty() {
+                        empty_clause = Some(index);
+                        break;
+                    }
+                }
+                ProofStep::Delete { literals, .. } => {
+                    // like drat-trim we ignore deleted unit clauses, they may be reasons of the top level
+                    if literals.len() == 1 {
+                        warn!(target: "check_drat", "ignoring deletion of unit clause: {:?}", literals);
+                        continue;
+                    }
+                    match self.delete_clause(literals) {
+                        Some(index) => self.applied.push(Applied::Delete(index)),
+                        None => warn!(target: "check_drat", "ignoring deletion of missing clause: {:?}", literals),
+                    }
+                }
+            }
+        }
+        info!(target: "check_drat", "read {} lemmas, the empty clause is {:?}", lemma, empty_clause);
+
+        // the empty clause itself is not needed for the final conflict
+        if let Some(index) = empty_clause {
+            self.clauses[index].active = false;
+            self.applied.pop();
+        }
+        self.final_hints = match self.rup(&[]) {
+            Some(hints) => hints,
+            None => return CheckResult::NoConflict,
+        };
+
+        let mut invalid = None;
+        for applied in self.applied.clone().into_iter().rev() {
+            match applied {
+                Applied::Add(index) => {
+                    self.clauses[index].active = false;
+                    if !self.clauses[index].core {
+                        continue;
+                    }
+                    let literals = self.clauses[index].literals.clone();
+                    let pivot = self.clauses[index].pivot;
+                    match self.rup(&literals).or_else(|| self.rat(pivot, &literals)) {
+                        Some(hints) => self.hints[index] = hints,
+                        None => {
+                            debug!(target: "check_drat", "lemma {} is invalid: {:?}", self.clauses[index].lemma, literals);
+                            invalid = Some(index);
+                        }
+                    }
+                }
+                Applied::Delete(index) => self.clauses[index].active = true,
+            }
+        }
+        match invalid {
+            Some(index) => CheckResult::Invalid {
+                lemma: self.clauses[index].lemma,
+                literals: self.clauses[index].literals.clone(),
+            },
+            None => CheckResult::Verified,
+        }
+    }
+
+    /// Check every lemma of an LRAT proof with its hints.
+    pub fn check_lrat(&mut self, steps: &[ProofStep]) -> CheckResult {
+        let mut database: HashMap<usize, Vec<i32>> = self
+            .clauses
+            .iter()
+            .enumerate()
+            .map(|(index, clause)| (index + 1, clause.literals.clone()))
+            .collect();
+        let mut last_id = self.num_original;
+        let mut lemma = 0;
+        for step in steps {
+            match step {
+                ProofStep::Add { id, literals, hints } => {
+                    lemma += 1;
+                    if *id <= last_id || !self.lrat_lemma(&database, literals, hints) {
+                        return CheckResult::Invalid {
+                            lemma,
+                            literals: literals.clone(),
+                        };
+                    }
+                    last_id = *id;
+                    self.lrat_hints.insert(*id, hints.clone());
+                    if literals.is_empty() {
+                        self.empty_clause_id = Some(*id);
+                        return CheckResult::Verified;
+                    }
+                    database.insert(*id, literals.clone());
+                }
+                ProofStep::Delete { ids, .. } => {
+                    for id in ids {
+                        database.remove(id);
+                    }
+                }
+            }
+        }
+        CheckResult::NoConflict
+    }
+
+    /// Follow the hints of a lemma, every hint has to be unit until one is falsified.
+    /// A negative hint starts the check of a RAT candidate on the first literal of the lemma.
+    fn lrat_lemma(&mut self, database: &HashMap<usize, Vec<i32>>, literals: &[i32], hints: &[i64]) -> bool {
+        self.unassign_all();
+        for lit in literals {
+            self.ensure_variable(*lit);
+            if self.value(*lit) == 1 {
+                return true;
+            }
+            if self.value(*lit) == 0 {
+                self.assign(-lit, None);
+            }
+        }
+        let rat_start = hints.iter().position(|hint| *hint < 0).unwrap_or(hints.len());
+        if self.lrat_units(database, &hints[..rat_start]) {
+            self.unassign_all();
+            return true;
+        }
+        let pivot = match literals.first() {
+            Some(pivot) => *pivot,
+            None => {
+                self.unassign_all();
+                return false;
+            }
+        };
+        // group the remaining hints by their candidate clause
+        let mut groups: HashMap<usize, &[i64]> = HashMap::new();
+        let mut start = rat_start;
+        while start < hints.len() {
+            let end = hints[start + 1..]
+                .iter()
+                .position(|hint| *hint < 0)
+                .map_or(hints.len(), |p| start + 1 + p);
+            groups.insert(hints[start].unsigned_abs() as usize, &hints[start + 1..end]);
+            start = end;
+        }
+        let prefix = self.trail.clone();
+        let mut valid = true;
+        for (id, candidate) in database.iter() {
+            if !candidate.contains(&-pivot) {
+                continue;
+            }
+            for lit in candidate {
+                self.ensure_variable(*lit);
+            }
+            // the resolvent is satisfied by the units of the lemma
+            if candidate.iter().any(|lit| *lit != -pivot && self.value(*lit) == 1) {
+                continue;
+            }
+            let group = match groups.get(id) {
+                Some(group) => *group,
+                None => {
+                    valid = false;
+                    break;
+                }
+            };
+            for lit in candidate {
+                if *lit != -pivot && self.value(*lit) == 0 {
+                    self.assign(-lit, None);
+                }
+            }
+            let conflict = self.lrat_units(database, group);
+            // go back to the assignment of the lemma
+            while self.trail.len() > prefix.len() {
+                let literal = self.trail.pop().unwrap();
+                self.values[variable(literal)] = 0;
+            }
+            if !conflict {
+                valid = false;
+                break;
+            }
+        }
+        self.unassign_all();
+        valid
+    }
+
+    fn lrat_units(&mut self, database: &HashMap<usize, Vec<i32>>, hints: &[i64]) -> bool {
+        for hint in hints {
+            let clause = match database.get(&(hint.unsigned_abs() as usize)) {
+                Some(clause) => clause,
+                None => return false,
+            };
+            let mut unit = None;
+            for lit in clause {
+                self.ensure_variable(*lit);
+                match self.value(*lit) {
+                    1 => return false,
+                    -1 => {}
+                    _ if unit.is_some() => return false,
+                    _ => unit = Some(*lit),
+                }
+            }
+            match unit {
+                Some(lit) => self.assign(lit, None),
+                None => return true,
+            }
+        }
+        false
+    }
+
+    /// The original clauses that are needed to derive the empty clause, with their DIMACS order.
+    pub fn core(&self) -> Vec<(usize, Vec<i32>)> {
+        let mut core = vec![false; self.num_original];
+        if let Some(empty_clause_id) = self.empty_clause_id {
+            // LRAT: follow the hints back from the empty clause
+            let mut stack = vec![empty_clause_id];
+            let mut visited = std::collections::HashSet::new();
+            while let Some(id) = stack.pop() {
+                if !visited.insert(id) {
+                    continue;
+                }
+                if id <= self.num_original {
+                    core[id - 1] = true;
+                } else if let Some(hints) = self.lrat_hints.get(&id) {
+                    stack.extend(hints.iter().map(|hint| hint.unsigned_abs() as usize));
+                }
+            }
+        } else {
+            for (index, clause) in self.clauses.iter().take(self.num_original).enumerate() {
+                core[index] = clause.core;
+            }
+        }
+        core.iter()
+            .enumerate()
+            .filter(|(_, core)| **core)
+            .map(|(index, _)| (index + 1, self.clauses[index].literals.clone()))
+            .collect()
+    }
+
+    /// Write the original clauses of the core as DIMACS CNF with their numbers.
+    pub fn write_core(&self, path: &PathBuf) -> std::io::Result<()> {
+        let num_variables = self.clauses[..self.num_original]
+            .iter()
+            .flat_map(|clause| clause.literals.iter())
+            .map(|lit| lit.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0);
+        write_numbered_clauses(path, num_variables, &self.core())
+    }
+
+    /// Write a checked DRAT proof as LRAT proof. Only the core lemmas are kept, the original
+    /// clauses keep their DIMACS numbers and the lemmas are numbered after them.
+    pub fn write_lrat(&self, path: &PathBuf) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let mut ids: Vec<Option<usize>> = (0..self.clauses.len())
+            .map(|index| (index < self.num_original).then_some(index + 1))
+            .collect();
+        let mut next_id = self.num_original + 1;
+        let write_hints = |writer: &mut BufWriter<File>, hints: &[Hint], ids: &[Option<usize>]| {
+            for hint in hints {
+                match hint {
+                    Hint::Unit(index) => write!(writer, "{} ", ids[*index].unwrap_or(0))?,
+                    Hint::Rat(index) => write!(writer, "-{} ", ids[*index].unwrap_or(0))?,
+                }
+            }
+            writeln!(writer, "0")
+        };
+        for applied in &self.applied {
+            match *applied {
+                Applied::Add(index) if self.clauses[index].core => {
+                    ids[index] = Some(next_id);
+                    write!(writer, "{} ", next_id)?;
+                    next_id += 1;
+                    // RAT lemmas are checked on their first literal
+                    let pivot = self.clauses[index].pivot;
+                    for lit in pivot.iter().chain(self.clauses[index].literals.iter().filter(|lit| Some(**lit) != pivot)) {
+                        write!(writer, "{} ", lit)?;
+                    }
+                    write!(writer, "0 ")?;
+                    write_hints(&mut writer, &self.hints[index], &ids)?;
+                }
+                Applied::Delete(index) => {
+                    if let Some(id) = ids[index] {
+                        writeln!(writer, "{} d {} 0", next_id - 1, id)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        write!(writer, "{} 0 ", next_id)?;
+        write_hints(&mut writer, &self.final_hints, &ids)?;
+        writer.flush()
+    }
+}
@@ -0,0 +1,310 @@
+use crate::dpll::dpll::dpll;
+use crate::dpll::schemas::{Formula, FormulaResultType};
+use log::{debug, info};
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::ops::{Add, Mul};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Exact model counter in the style of DPLL.
+///
+/// After every decision the formula is simplified by unit propagation and split into components
+/// that share no variable, the count of the formula is the product of the counts of its components.
+/// The count of every component is cached by its clauses, so a component that appears again in
+/// another branch is counted only once.
+/// With a projection only the distinct assignments of the projected variables are counted. The
+/// counter branches on projected variables only, a component without projected variables counts
+/// 1 if it is satisfiable and 0 otherwise.
+/// With weights every model counts the product of the weights of its (projected) literals, the
+/// count can be any number type like [`BigUint`], [`BigRational`] or f64.
+#[derive(Debug)]
+pub struct ModelCounter<W: Weight = BigUint> {
+    clauses: Vec<Vec<i16>>,
+    num_variables: usize,
+    // projected[variable_index], all variables without projection
+    projected: Vec<bool>,
+    // weights of the positive and the negative literal by variable index
+    weights: Vec<(W, W)>,
+    cache: HashMap<Vec<Vec<i16>>, W>,
+    pub decisions: usize,
+    pub cache_hits: usize,
+}
+
+/// The number type of a (weighted) model count.
+pub trait Weight: Clone + Debug + Zero + One + Add<Output = Self> + Mul<Output = Self> {}
+
+impl<T: Clone + Debug + Zero + One + Add<Output = T> + Mul<Output = T>> Weight for T {}
+
+impl ModelCounter {
+    /// Count the models projected onto the given variables, without projection all variables are used.
+    /// Returns an error if a projection variable is not a variable of the formula.
+    pub fn new(num_variables: usize, clauses: Vec<Vec<i16>>, projection: Option<Vec<i16>>) -> Result<Self, String> {
+        let projected = match projection {
+            Some(variables) => {
+                let mut projected = vec![false; num_variables];
+                for variable in variables {
+                    if variable <= 0 || variable as usize > num_variables {
+                        return Err(format!("projection variable {} is not a variable of the formula", variable));
+                    }
+                    projected[(variable - 1) as usize] = true;
+                }
+                projected
+            }
+            None => vec![true; num_variables],
+        };
+        Ok(Self {
+            clauses,
+            num_variables,
+            projected,
+            weights: vec![(BigUint::one(), BigUint::one()); num_variables],
+            cache: HashMap::new(),
+            decisions: 0,
+            cache_hits: 0,
+        })
+    }
+
+    /// Count with weights of the given number type, literals without weight have the weight 1.
+    /// Returns an error if a literal is not a literal of the formula.
+    pub fn with_weights<W: Weight>(self, weights: Vec<(i16, W)>) -> Result<ModelCounter<W>, String> {
+        let mut counter = ModelCounter {
+            clauses: self.clauses,
+            num_variables: self.num_variables,
+            projected: self.projected,
+            weights: vec![(W::one(), W::one()); self.num_variables],
+            cache: HashMap::new(),
+            decisions: 0,
+            cache_hits: 0,
+        };
+        for (literal, weight) in weights {
+            if literal == 0 || literal.unsigned_abs() as usize > counter.num_variables {
+                return Err(format!("weighted literal {} is not a literal of the formula", literal));
+            }
+            let variable_weights = &mut counter.weights[variable_index(literal)];
+            if literal > 0 {
+                variable_weights.0 = weight;
+            } else {
+                variable_weights.1 = weight;
+            }
+        }
+        Ok(counter)
+    }
+}
+
+impl<W: Weight> ModelCounter<W> {
+    fn literal_weight(&self, variable_index: usize, value: bool) -> W {
+        if value {
+            self.weights[variable_index].0.clone()
+        } else {
+            self.weights[variable_index].1.clone()
+        }
+    }
+
+    /// The number of models of the formula, or of distinct assignments of the projected variables.
+    pub fn count(&mut self) -> W {
+        let variables: BTreeSet<usize> = (0..self.num_variables).collect();
+        let mut clauses = Vec::with_capacity(self.clauses.len());
+        for clause in &self.clauses {
+            let mut literals: Vec<i16> = Vec::with_capacity(clause.len());
+            for lit in clause {
+                if !literals.contains(lit) {
+                    literals.push(*lit);
+                }
+            }
+            // tautologies are satisfied by every assignment
+            if !literals.iter().any(|lit| literals.contains(&-lit)) {
+                clauses.push(literals);
+            }
+        }
+        let count = self.count_assigned(&clauses, &variables, None);
+        info!(target: "count", "{} decisions, {} cache hits, {} cached components", self.decisions, self.cache_hits, self.cache.len());
+        count
+    }
+
+    /// Assign the literal, propagate the units and multiply the counts of the components of the rest.
+    /// Variables that no longer occur are free, every projected one multiplies the count with the
+    /// sum of the weights of its literals, which doubles an unweighted count.
+    fn count_assigned(&mut self, clauses: &[Vec<i16>], variables: &BTreeSet<usize>, literal: Option<i16>) -> W {
+        let (residual, assigned) = match propagate(clauses, literal) {
+            Some(result) => result,
+            None => return W::zero(),
+        };
+        let mut remaining = BTreeSet::new();
+        for lit in residual.iter().flatten() {
+            remaining.insert(variable_index(*lit));
+        }
+        let mut count = W::one();
+        for (variable, value) in &assigned {
+            if self.projected[*variable] {
+                count = count * self.literal_weight(*variable, *value);
+            }
+        }
+        for variable in variables {
+            if self.projected[*variable] && !assigned.contains_key(variable) && !remaining.contains(variable) {
+                count = count * (self.literal_weight(*variable, true) + self.literal_weight(*variable, false));
+            }
+        }
+        for component in components(residual) {
+            count = count * self.count_component(component);
+            if count.is_zero() {
+                break;
+            }
+        }
+        count
+    }
+
+    fn count_component(&mut self, clauses: Vec<Vec<i16>>) -> W {
+        if let Some(count) = self.cache.get(&clauses) {
+            self.cache_hits += 1;
+            return count.clone();
+        }
+        let mut occurrences: HashMap<usize, usize> = HashMap::new();
+        for lit in clauses.iter().flatten() {
+            *occurrences.entry(variable_index(*lit)).or_default() += 1;
+        }
+        let variables: BTreeSet<usize> = occurrences.keys().copied().collect();
+        // branch on the projected variable with the most occurrences
+        let branch = occurrences
+            .iter()
+            .filter(|(variable, _)| self.projected[**variable])
+            .max_by_key(|(variable, occurrences)| (**occurrences, std::cmp::Reverse(**variable)))
+            .map(|(variable, _)| (*variable + 1) as i16);
+        let count = match branch {
+            Some(variable) => {
+                self.decisions += 1;
+                debug!(target: "count_component", "branch on {} in component with {} clauses", variable, clauses.len());
+                self.count_assigned(&clauses, &variables, Some(variable))
+                    + self.count_assigned(&clauses, &variables, Some(-variable))
+            }
+            None if satisfiable(&clauses) => W::one(),
+            None => W::zero(),
+        };
+        self.cache.insert(clauses, count.clone());
+        count
+    }
+}
+
+fn variable_index(literal: i16) -> usize {
+    (literal.unsigned_abs() - 1) as usize
+}
+
+// the clauses left after unit propagation and the values of the assigned variables by index
+type Propagated = (Vec<Vec<i16>>, HashMap<usize, bool>);
+
+/// Simplify the clauses by the literal and all units that follow from it. Returns the clauses that
+/// are neither satisfied nor unit, and the values of the assigned variables, or None on a conflict.
+fn propagate(clauses: &[Vec<i16>], literal: Option<i16>) -> Option<Propagated> {
+    let mut values: HashMap<usize, bool> = HashMap::new();
+    if let Some(lit) = literal {
+        values.insert(variable_index(lit), lit > 0);
+    }
+    let mut current: Vec<Vec<i16>> = clauses.to_vec();
+    loop {
+        let mut changed = false;
+        let mut residual = Vec::with_capacity(current.len());
+        for clause in current {
+            let mut literals = Vec::with_capacity(clause.len());
+            let mut satisfied = false;
+            for lit in clause {
+                match values.get(&variable_index(lit)) {
+                    Some(value) if *value == (lit > 0) => {
+                        satisfied = true;
+                        break;
+                    }
+                    Some(_) => {}
+                    None => literals.push(lit),
+                }
+            }
+            if satisfied {
+                continue;
+            }
+            match literals.len() {
+                0 => return None,
+                1 => {
+                    // a second unit with the opposite literal is a conflict in the next round
+                    values.insert(variable_index(literals[0]), literals[0] > 0);
+                    changed = true;
+                }
+                _ => residual.push(literals),
+            }
+        }
+        current = residual;
+        if !changed {
+            return Some((current, values));
+        }
+    }
+}
+
+/// Split the clauses into groups that share no variable, every group is sorted so equal
+/// components have equal keys in the cache.
+fn components(clauses: Vec<Vec<i16>>) -> Vec<Vec<Vec<i16>>> {
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    fn find(parent: &mut HashMap<usize, usize>, variable: usize) -> usize {
+        let mut root = variable;
+        while let Some(next) = parent.get(&root).copied().filter(|next| *next != root) {
+            root = next;
+        }
+        parent.insert(variable, root);
+        root
+    }
+    for clause in &clauses {
+        let first = find(&mut parent, variable_index(clause[0]));
+        for lit in &clause[1..] {
+            let root = find(&mut parent, variable_index(*lit));
+            if root != first {
+                parent.insert(root, first);
+            }
+        }
+    }
+    let mut groups: HashMap<usize, Vec<Vec<i16>>> = HashMap::new();
+    for mut clause in clauses {
+        clause.sort_unstable();
+        let root = find(&mut parent, variable_index(clause[0]));
+        groups.entry(root).or_default().push(clause);
+    }
+    groups
+        .into_values()
+        .map(|mut group| {
+            group.sort_unstable();
+            group
+        })
+        .collect()
+}
+
+/// Solve a component without projected variables with the CDCL solver.
+fn satisfiable(clauses: &[Vec<i16>]) -> bool {
+    let num_variables = clauses.iter().flatten().map(|lit| lit.unsigned_abs() as usize).max().unwrap_or(0);
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula.result() == FormulaResultType::Satisfiable
+}
+
+/// Parse an exact weight like `0.25`, `1/3` or `2.5e-3`.
+pub fn parse_rational(weight: &str) -> Option<BigRational> {
+    if let Some((numerator, denominator)) = weight.split_once('/') {
+        let denominator = denominator.parse::<BigInt>().ok()?;
+        if denominator.is_zero() {
+            return None;
+        }
+        return Some(BigRational::new(numerator.parse().ok()?, denominator));
+    }
+    let (mantissa, exponent) = match weight.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().ok()?),
+        None => (weight, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits: BigInt = format!("{}{}", integer, fraction).parse().ok()?;
+    let exponent = exponent - fraction.len() as i32;
+    let power = num_traits::pow(BigInt::from(10), exponent.unsigned_abs() as usize);
+    Some(if exponent >= 0 {
+        BigRational::from_integer(digits * power)
+    } else {
+        BigRational::new(digits, power)
+    })
+}
@@ -1,6 +1,10 @@
-use crate::dpll::schemas::{AssigmentType, Assignment, Formula, FormulaResultType, HeuristicType, PureType, SetResultType, Value};
+use crate::dpll::local_search::update_phases;
+use crate::dpll::probe::probe;
+use crate::dpll::vivify::vivify;
+use crate::dpll::schemas::{AssigmentType, Assignment, Clause, ClauseType, Formula, FormulaResultType, HeuristicType, PureType, SetResultType, Value};
+use crate::dpll::xor::XorPropagation;
 use log::{debug, warn};
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -51,10 +55,12 @@ fn set_variable_true(
             }
             Err(_) => {
                 warn!(target: "set_variable_true","conflict fore clause: {:?} index: {}", formula.clauses[*clause_index], clause_index);
-                let (depth, clause) = analyse_conflict_with_decision_scheme(variable_index, formula);
+                let (depth, clause, hints) =
+                    analyse_conflict_with_decision_scheme(variable_index, *clause_index, formula);
                 result = SetResultType::Conflict {
                     depth,
                     clause,
+                    hints,
                 };
                 // Update clauses activity for BerkMin's
                 formula.clauses[*clause_index].activity += 1;
@@ -62,7 +68,7 @@ fn set_variable_true(
         }
     }
     debug!(target: "set_variable_true", "result of set variable is: {:?}", &result);
-    return result;
+    result
 }
 
 fn set_variable_false(
@@ -112,10 +118,12 @@ fn set_variable_false(
             }
             Err(_) => {
                 warn!(target: "set_variable_false","conflict fore clause: {:?} index: {}", formula.clauses[*clause_index], clause_index);
-                let (depth, clause) = analyse_conflict_with_decision_scheme(variable_index, formula);
+                let (depth, clause, hints) =
+                    analyse_conflict_with_decision_scheme(variable_index, *clause_index, formula);
                 result = SetResultType::Conflict {
                     depth,
                     clause,
+                    hints,
                 };
                 // Update clauses activity for BerkMin's
                 formula.clauses[*clause_index].activity += 1;
@@ -123,7 +131,7 @@ fn set_variable_false(
         }
     }
     debug!(target: "set_variable_false", "result of set variable is: {:?}", &result);
-    return result;
+    result
 }
 
 /// Undo the assigment of a variable for backtracking.
@@ -162,7 +170,7 @@ fn dfs(
         for &clause_idx in formula.variables[vertex].positive_occurrences.iter().chain(formula.variables[vertex].negative_occurrences.iter()) {
             let clause = &formula.clauses[clause_idx];
             for &literal in &clause.literals {
-                let neighbor = (literal.abs() - 1) as usize;
+                let neighbor = (literal.unsigned_abs() - 1) as usize;
                 if !visited[neighbor] {
                     stack.push_back(neighbor);
                     visited[neighbor] = true;
@@ -174,8 +182,18 @@ fn dfs(
 }
 
 /// Cut based on decision and 1UIP scheme and add an asserting conflict clause.
-/// Find and give second-largest branching depth.
-fn analyse_conflict_with_decision_scheme(variable_index: usize, formula: &mut Formula) -> (usize, Vec<i16>) {
+/// Find and give the second-largest branching depth of the literals of the learned clause.
+/// For LRAT proofs the reasons of the implied variables that lead to the conflict are collected as hints.
+fn analyse_conflict_with_decision_scheme(
+    variable_index: usize,
+    conflict_clause: usize,
+    formula: &mut Formula,
+) -> (usize, Vec<i16>, Vec<usize>) {
+    let hints = if formula.proof_with_hints() {
+        formula.resolution_hints(conflict_clause)
+    } else {
+        Vec::new()
+    };
     let reachable_vertices = dfs(variable_index, formula);
     debug!(target: "analyse_conflict_with_decision_scheme", "variable_index: {},reachable_vertices: {:?}",variable_index, reachable_vertices);
     let mut depths: Vec<usize> = Vec::new();
@@ -189,10 +207,10 @@ fn analyse_conflict_with_decision_scheme(variable_index: usize, formula: &mut Fo
         }
         // All branching vertices from which conflict clause can be reached.
         debug!(target: "analyse_conflict_with_decision_scheme", "literal: {}", literal);
-        if formula.variables[literal].reason == None {
+        if formula.variables[literal].reason.is_none() {
             debug!(target: "analyse_conflict_with_decision_scheme", "formula.variables[literal]: {:?}", formula.variables[literal]);
             match formula.variables[literal].value {
-                Value::True => conflict_clause_literal.push(-1 * ((literal + 1) as i16)),
+                Value::True => conflict_clause_literal.push(-((literal + 1) as i16)),
                 Value::False => conflict_clause_literal.push((literal + 1) as i16),
                 _ => {
                     warn!(target: "analyse_conflict_with_decision_scheme", "branching_vertices should have assigned values");
@@ -203,63 +221,64 @@ fn analyse_conflict_with_decision_scheme(variable_index: usize, formula: &mut Fo
     }
     debug!(target: "analyse_conflict_with_decision_scheme", "new clause to learn: {:?}", &conflict_clause_literal);
 
-    let mut uip_clause = conflict_clause_literal.clone();
+    // The learned clause asserts its deepest literal after backtracking to the second-highest depth
+    // of its literals. The decisions have distinct depths, if two literals share the highest one
+    // anyway the clause is only valid below it.
+    depths.sort_unstable_by(|a, b| b.cmp(a));
+    let backjump = match depths[..] {
+        [] | [_] => 0,
+        [first, second, ..] if first == second => first.saturating_sub(1),
+        [_, second, ..] => second,
+    };
+    debug!(target: "analyse_conflict_with_decision_scheme", "max depth: {}, backjump to: {}", max_decision_level, backjump);
+    (backjump, conflict_clause_literal, hints)
+}
 
-    // Remove literals with decision level bigger than max decision level.
-    uip_clause.retain(|&literal| {
-        let decision_level = formula.assigment_stack
-            .iter()
-            .find(|&assignment| assignment.variable_index == (literal.abs() - 1) as usize)
-            .map(|assignment| assignment.depth)
-            .unwrap_or(0); // Default to 0 if not found
-        decision_level <= max_decision_level
-    });
-
-    if uip_clause.len() == 1 {
-        warn!(target: "analyse_conflict_with_decision_scheme", "depth 0 !!");
-        (0, uip_clause)
-    } else {
-        (max_decision_level - 1, conflict_clause_literal)
+/// Find the activation literals that are responsible for a failed assumption.
+/// Like the conflict analysis this collects all decided assumptions that can be reached from the
+/// failed one, so the result is a superset of the assumptions that really conflict.
+fn analyse_final(literal: i16, formula: &mut Formula) -> Vec<i16> {
+    let mut failed = vec![literal];
+    for vertex in dfs((literal.unsigned_abs() - 1) as usize, formula) {
+        let assumption = if formula.variables[vertex].value == Value::True {
+            (vertex + 1) as i16
+        } else {
+            -((vertex + 1) as i16)
+        };
+        if formula.variables[vertex].reason.is_none()
+            && formula.is_assumption(assumption)
+            && !failed.contains(&assumption)
+        {
+            failed.push(assumption);
+        }
     }
+    failed
 }
 
-/// Backtrack the forced assigment
+/// Backtrack the assignments above the given depth
 ///
-/// First we undo all the Forced assignments, if the assignment stack get empty in this process the formula is unsat.
-/// If we still got a Branched assigment we undo this as well empty our unit queue and set the variable to false.
-/// Then we're going back to normal and start with unit propagation and regular assignments.
-fn backtrack(
+/// All assignments with a higher depth are undone, the assignments on the given depth and below stay.
+/// Unsatisfiability is detected by the empty learned clause, not by backtracking.
+pub(crate) fn backtrack(
     formula: &mut Formula,
     depth: usize,
 ) -> Option<FormulaResultType> {
     debug!(target: "backtrack", "backtracking everything that have a higher depth than: {}", depth);
     debug!(target: "backtrack", "current_assigment_stack: {:?}", &formula.assigment_stack);
-    while let Some(top) = formula.assigment_stack_pop() {
-        // undo all assigment where the depth is bigger than the given depth
-        if top.depth > depth {
-            debug!(target: "backtrack", "undo assigment: {:?}", &top);
-            undo_assignment(top.variable_index, formula);
-            //continue;
-        } else {
-            debug!(target: "backtrack", "done backtracking assigment: {:?}", &top);
-            //dbg!(&formula.assigment_stack);
-            formula.depth = depth;
-            //formula.units.clear();
-            return None;
+    // undo all assigment where the depth is bigger than the given depth, the first assigment with
+    // a lower or equal depth stays on the stack.
+    while let Some(top) = formula.assigment_stack.last() {
+        if top.depth <= depth {
+            debug!(target: "backtrack", "done backtracking assigment: {:?}", top);
+            break;
         }
-        // undo all assigment where the depth is equal to the given depth and the assigment where forced
-        /*if top.depth == depth && top.assigment_type == AssigmentType::Forced {
-            undo_assignment(top.variable_index, formula);
-            continue;
-        }
-        // if we reach the assigment where the depths are equal and the AssignmentType is Branching we are done backtracking.
-        if top.depth == depth && top.assigment_type == AssigmentType::Branching {
-            *gbd = depth;
-            return None;
-        }*/
+        let top = formula.assigment_stack_pop().unwrap();
+        debug!(target: "backtrack", "undo assigment: {:?}", &top);
+        undo_assignment(top.variable_index, formula);
     }
     debug!(target: "backtrack", "Backtrack finished");
-    return Some(FormulaResultType::Unsatisfiable);
+    formula.depth = depth;
+    None
 }
 
 fn berk_mins_clause_deletion_strategies(formular: &mut Formula, threshold: u16) {
@@ -276,6 +295,13 @@ fn berk_mins_clause_deletion_strategies(formular: &mut Formula, threshold: u16)
     (formular.original_clause_vector_length..formular.clauses.len()).enumerate()
     {
         let clause = &formular.clauses[clause_index];
+        // original clauses added between two solve calls and reasons of assigned variables stay
+        if clause.clause_type != ClauseType::Learned
+            || clause.deleted
+            || formular.is_reason(clause_index)
+        {
+            continue;
+        }
         if index <= first_sixteentel {
             // old
             if clause.activity <= threshold && clause.literals.len() > 8 {
@@ -293,87 +319,117 @@ fn berk_mins_clause_deletion_strategies(formular: &mut Formula, threshold: u16)
     }
 }
 
-fn unit_propagation(formula: &mut Formula) -> Option<FormulaResultType> {
-    while let Some((unit, value, clause_index)) = formula.units.pop_front() {
-        // Forced Assigment because of unit propagation !
-        //let unit = formula.units.pop_front().unwrap();
-        if formula.variables[unit].value != Value::Null {
-            //panic!("Variable: {} is already set", unit);
-            //warn!(target: "unit_propagation", "Variable: {} is already set", unit);
-            continue;
-        }
-        debug!(target: "unit_propagation", "Unit propagation: {}", unit);
-        let result;
-        match value {
-            Value::True => {
-                result = set_variable_true(
-                    unit,
-                    formula,
-                    AssigmentType::Forced,
-                    Some(clause_index),
-                );
-            }
-            Value::False => {
-                result = set_variable_false(
-                    unit,
-                    formula,
-                    AssigmentType::Forced,
-                    Some(clause_index),
-                );
-            }
-            Value::Null => {
-                panic!("i cannot set a unit to the value none in unit propagation")
-            }
-        }
-
-        match result {
-            SetResultType::Success => {
+pub(crate) fn unit_propagation(formula: &mut Formula) -> Option<FormulaResultType> {
+    loop {
+        while let Some((unit, value, clause_index)) = formula.units.pop_front() {
+            // Forced Assigment because of unit propagation !
+            //let unit = formula.units.pop_front().unwrap();
+            if formula.variables[unit].value != Value::Null {
+                //panic!("Variable: {} is already set", unit);
+                //warn!(target: "unit_propagation", "Variable: {} is already set", unit);
                 continue;
             }
-            SetResultType::Conflict { depth, clause } => {
-                if depth == 0 {
-                    formula.result = FormulaResultType::Unsatisfiable;
-                    return Some(FormulaResultType::Unsatisfiable);
+            debug!(target: "unit_propagation", "Unit propagation: {}", unit);
+            let result;
+            match value {
+                Value::True => {
+                    result = set_variable_true(
+                        unit,
+                        formula,
+                        AssigmentType::Forced,
+                        Some(clause_index),
+                    );
                 }
-                match formula.heuristic_type {
-                    HeuristicType::VSIDS => {
-                        formula.vsids_score(unit);
-                    }
-                    _ => {}
+                Value::False => {
+                    result = set_variable_false(
+                        unit,
+                        formula,
+                        AssigmentType::Forced,
+                        Some(clause_index),
+                    );
                 }
+                Value::Null => {
+                    panic!("i cannot set a unit to the value none in unit propagation")
+                }
+            }
 
-                debug!(target: "unit_propagation", "Unit propagation failed backtracking again");
-                // after backtracking the unit queue should be empty. so we're exiting the loop automatically.
-                match backtrack(formula, depth) {
-                    None => {
-                        debug!(target: "unit_propagation", "successful backtracked");
-                    }
-                    Some(result) => {
-                        warn!(target: "unit_propagation", "unsuccessful backtracked: {:?}", &result);
-                        formula.result = result;
+            match result {
+                SetResultType::Success => {
+                    continue;
+                }
+                SetResultType::Conflict { depth, clause, hints } => {
+                    if let Some(result) = learn_conflict_clause(formula, unit, depth, clause, hints) {
                         return Some(result);
                     }
                 }
-                match formula.add_clauses(clause){
-                    Some(r) =>{
-                        formula.result = r;
-                        return Some(r);
-                    }
-                    _ => {}
+            }
+        }
+        // the xor constraints are propagated when no clause has a unit left
+        match formula.gauss_propagate() {
+            XorPropagation::Fixpoint => return None,
+            XorPropagation::Units => {}
+            XorPropagation::Conflict(clause_index) => {
+                let Some(lit) = formula.clauses[clause_index].literals.first().copied() else {
+                    // the xor constraints contradict each other without any assignment
+                    formula.proof_add(Clause::id(formula.clauses.len()), &[], &[]);
+                    formula.result = FormulaResultType::Unsatisfiable;
+                    return Some(FormulaResultType::Unsatisfiable);
+                };
+                let variable_index = (lit.unsigned_abs() - 1) as usize;
+                let (depth, clause, hints) =
+                    analyse_conflict_with_decision_scheme(variable_index, clause_index, formula);
+                if let Some(result) = learn_conflict_clause(formula, variable_index, depth, clause, hints) {
+                    return Some(result);
                 }
             }
         }
     }
-    return None;
+}
+
+/// Backtrack after a conflict and add the learned clause, whose asserted literal is propagated next.
+fn learn_conflict_clause(
+    formula: &mut Formula,
+    variable_index: usize,
+    depth: usize,
+    clause: Vec<i16>,
+    hints: Vec<usize>,
+) -> Option<FormulaResultType> {
+    formula.conflicts += 1;
+    if clause.is_empty() {
+        formula.proof_add(Clause::id(formula.clauses.len()), &[], &hints);
+        formula.result = FormulaResultType::Unsatisfiable;
+        return Some(FormulaResultType::Unsatisfiable);
+    }
+    if formula.heuristic_type == HeuristicType::VSIDS {
+        formula.vsids_score(variable_index);
+    }
+
+    debug!(target: "unit_propagation", "Unit propagation failed backtracking again");
+    // after backtracking the unit queue should be empty. so we're exiting the loop automatically.
+    match backtrack(formula, depth) {
+        None => {
+            debug!(target: "unit_propagation", "successful backtracked");
+        }
+        Some(result) => {
+            warn!(target: "unit_propagation", "unsuccessful backtracked: {:?}", &result);
+            formula.result = result;
+            return Some(result);
+        }
+    }
+    if let Some(r) = formula.add_clauses(clause, hints) {
+        formula.result = r;
+        return Some(r);
+    }
+    None
 }
 
 fn scan_for_units(formula: &mut Formula) {
     for (clause_index, clause) in formula.clauses.iter().enumerate() {
-        if clause.watched.0 == clause.watched.1 {
+        if clause.watched.0 == clause.watched.1 && !clause.deleted {
             debug!(target: "scan_for_units", "unit found! {:?}", clause);
             let lit = clause.literals[clause.watched.0];
             let value = if lit > 0 { Value::True } else { Value::False };
-            formula.units.push_back(((lit.abs() - 1) as usize, value, clause_index));
+            formula.units.push_back(((lit.unsigned_abs() - 1) as usize, value, clause_index));
         }
     }
 }
@@ -385,38 +441,47 @@ fn pure_literal_elimination(formula: &mut Formula) {
     for index in 0..formula.variables.len() {
         let variable_index = formula.variables_index[index].0;
         let variable = &formula.variables[variable_index];
-        if variable.value != Value::Null {
+        // activation literals only occur negated but have to be decided as assumption
+        let literal = (variable_index + 1) as i16;
+        if variable.value != Value::Null || formula.is_assumption(literal) || formula.is_assumption(-literal) {
             continue;
         }
-        match variable.is_pure() {
-            Some(pure) => {
-                debug!("Pure positive: {}", variable_index + 1);
-                let value = match pure {
-                    PureType::Positive => set_variable_true(variable_index + 1, formula, AssigmentType::Branching, None),
-                    PureType::Negative => set_variable_false(variable_index + 1, formula, AssigmentType::Branching, None),
-                };
-                match value {
-                    SetResultType::Success => {}
-                    SetResultType::Conflict { depth, clause } => {
-                        warn!(target: "pure_literal_elimination", "formular unsat in depth: {}, clause: {:?}", depth, clause);
-                        formula.result = FormulaResultType::Unsatisfiable;
-                        return;
-                    }
+        // the variables of xor constraints are not pure, even if they occur in no clause
+        if let Some(pure) = variable.is_pure().filter(|_| !formula.is_xor_variable(variable_index)) {
+            debug!("Pure positive: {}", variable_index + 1);
+            let value = match pure {
+                PureType::Positive => set_variable_true(variable_index, formula, AssigmentType::Branching, None),
+                PureType::Negative => set_variable_false(variable_index, formula, AssigmentType::Branching, None),
+            };
+            match value {
+                SetResultType::Success => {}
+                SetResultType::Conflict { depth, clause, .. } => {
+                    warn!(target: "pure_literal_elimination", "formular unsat in depth: {}, clause: {:?}", depth, clause);
+                    formula.result = FormulaResultType::Unsatisfiable;
+                    return;
                 }
             }
-            None => {}
         }
     }
 }
 
 pub fn dpll(formula: &mut Formula, timeout: Arc<AtomicBool>) {
     let mut index = 0;
+    // go back to the root level in case the formula was already solved before
+    formula.reset();
+    if formula.result == FormulaResultType::Unsatisfiable {
+        return;
+    }
+    formula.preprocess();
+    if formula.result == FormulaResultType::Unsatisfiable {
+        return;
+    }
     scan_for_units(formula);
-    match unit_propagation(formula) {
-        Some(_) => {
-            return;
-        }
-        _ => {}
+    if unit_propagation(formula).is_some() {
+        return;
+    }
+    if formula.preprocess.probing && probe(formula, timeout.clone()).is_some() {
+        return;
     }
 
     pure_literal_elimination(formula);
@@ -424,57 +489,89 @@ pub fn dpll(formula: &mut Formula, timeout: Arc<AtomicBool>) {
         formula.result = FormulaResultType::Unsatisfiable;
         return;
     }
+    let mut next_phases = formula.local_search_phases.as_ref().map(|phases| formula.conflicts + phases.interval);
+    let mut next_inprocess = formula.conflicts + formula.preprocess.inprocess_interval;
 
     loop {
         debug!(target: "dpll", "current index: {}", index);
-        if index == formula.variables.len() {
-            formula.result = FormulaResultType::Satisfiable;
-            return;
-        }
-        debug!("current variables index: {:?}", formula.variables_index);
-        let variable_index = formula.variables_index[index].0;
+        // the activation literals of all open scopes and the assumptions are decided before any other variable
+        let assumption = formula
+            .scopes
+            .iter()
+            .chain(formula.assumptions.iter())
+            .copied()
+            .find(|lit| formula.literal_value(*lit) != Value::True);
+        let (variable_index, value) = match assumption {
+            Some(literal) if formula.literal_value(literal) == Value::False => {
+                formula.failed_assumptions = analyse_final(literal, formula);
+                debug!(target: "dpll", "assumption {} failed: {:?}", literal, &formula.failed_assumptions);
+                formula.result = FormulaResultType::Unsatisfiable;
+                return;
+            }
+            Some(literal) => ((literal.unsigned_abs() - 1) as usize, literal > 0),
+            None => {
+                if index == formula.variables.len() {
+                    formula.reconstruct_model();
+                    formula.result = FormulaResultType::Satisfiable;
+                    return;
+                }
+                debug!("current variables index: {:?}", formula.variables_index);
+                let variable_index = formula.variables_index[index].0;
 
-        berk_mins_clause_deletion_strategies(formula, 7);
+                berk_mins_clause_deletion_strategies(formula, 7);
 
-        debug!(target: "dpll", "current variable index: {}", variable_index);
-        if formula.variables[variable_index].value != Value::Null {
-            debug!(target: "dpll", "Variable: {} is already set", variable_index + 1);
-            index += 1;
-            continue;
-        }
+                // the local search runs between the decisions, so it sees the current assignment
+                if next_phases.is_some_and(|conflicts| formula.conflicts >= conflicts) {
+                    update_phases(formula, timeout.clone());
+                    next_phases = formula.local_search_phases.as_ref().map(|phases| formula.conflicts + phases.interval);
+                }
+                if formula.inprocessing() && formula.conflicts >= next_inprocess {
+                    formula.inprocess();
+                    next_inprocess = formula.conflicts + formula.preprocess.inprocess_interval;
+                    // the probing and the vivification work on the root level, so the search restarts
+                    if formula.preprocess.probing || formula.preprocess.vivification {
+                        backtrack(formula, 0);
+                        if formula.preprocess.probing && probe(formula, timeout.clone()).is_some() {
+                            return;
+                        }
+                        if formula.preprocess.vivification && vivify(formula, timeout.clone()).is_some() {
+                            return;
+                        }
+                        index = 0;
+                        continue;
+                    }
+                }
+
+                debug!(target: "dpll", "current variable index: {}", variable_index);
+                if formula.variables[variable_index].value != Value::Null {
+                    debug!(target: "dpll", "Variable: {} is already set", variable_index + 1);
+                    index += 1;
+                    continue;
+                }
+                // decide the saved phase, it is true unless a local search run found a better one
+                (variable_index, formula.variables[variable_index].phase)
+            }
+        };
         debug!(target: "dpll", "Variable Value: {:?} ", formula.variables[variable_index]);
         if timeout.load(Ordering::SeqCst) {
             formula.result = FormulaResultType::Timeout;
             return;
         }
-        // start by setting the first variable to true
         // Branching type because we decided freely to set this variable!
         // theoretically we can ignore the result is the set variable true here, because a conflict can only occur if
         // we set variables though unit propagation.
-        match set_variable_true(variable_index, formula, AssigmentType::Branching, None) {
+        let decision = if value {
+            set_variable_true(variable_index, formula, AssigmentType::Branching, None)
+        } else {
+            set_variable_false(variable_index, formula, AssigmentType::Branching, None)
+        };
+        match decision {
             SetResultType::Success => {}
-            SetResultType::Conflict { depth, clause } => {
-                if depth == 0 {
-                    formula.result = FormulaResultType::Unsatisfiable;
-                    debug!(target: "dpll", "conflict depth is 0, {:?}", &formula.result);
+            SetResultType::Conflict { depth, clause, hints } => {
+                if let Some(result) = learn_conflict_clause(formula, variable_index, depth, clause, hints) {
+                    debug!(target: "dpll", "conflict of the decision failed: {:?}", &result);
                     return;
                 }
-                match backtrack(formula, depth) {
-                    None => {}
-                    Some(result) => {
-                        formula.result = result;
-                        debug!(target: "dpll","set_variable_true Backtrack failed: {:?}", &formula.result);
-                        return;
-                    }
-                }
-                match formula.add_clauses(clause){
-                    None => {}
-                    Some(result) => {
-                        formula.result = result;
-                        debug!(target: "dpll","set_variable_true Backtrack failed: {:?}", &formula.result);
-                        return;
-                    }
-                }
             }
         }
         //pure_literal_elimination(formula);
@@ -482,13 +579,9 @@ pub fn dpll(formula: &mut Formula, timeout: Arc<AtomicBool>) {
 
         index = 0;
         // propagate the units that have to be true now
-        // propagate the units that have to be true now
         debug!(target: "dpll", "time for unit propagation!");
-        match unit_propagation(formula) {
-            Some(_) => {
-                return;
-            }
-            _ => {}
+        if unit_propagation(formula).is_some() {
+            return;
         }
     }
 }
@@ -0,0 +1,126 @@
+use crate::dpll::schemas::{Clause, ClauseType, Formula, FormulaResultType, Value};
+use log::debug;
+
+/// The resolvent of two clauses on the given literal, which occurs positive in the first and
+/// negative in the second clause. Returns None if the resolvent is a tautology.
+pub(crate) fn resolve(positive: &[i16], negative: &[i16], literal: i16) -> Option<Vec<i16>> {
+    let mut resolvent: Vec<i16> = positive.iter().copied().filter(|lit| *lit != literal).collect();
+    for lit in negative {
+        if *lit == -literal || resolvent.contains(lit) {
+            continue;
+        }
+        if resolvent.contains(&-lit) {
+            return None;
+        }
+        resolvent.push(*lit);
+    }
+    Some(resolvent)
+}
+
+impl Formula {
+    /// The indexes of the original clauses that contain the literal.
+    pub(crate) fn original_occurrences(&self, literal: i16) -> Vec<usize> {
+        let variable = &self.variables[(literal.unsigned_abs() - 1) as usize];
+        let occurrences = if literal > 0 { &variable.positive_occurrences } else { &variable.negative_occurrences };
+        occurrences
+            .iter()
+            .copied()
+            .filter(|clause_index| self.clauses[*clause_index].clause_type != ClauseType::Learned)
+            .collect()
+    }
+
+    /// Bounded variable elimination in the style of SatELite.
+    ///
+    /// A variable is eliminated by replacing all original clauses it occurs in with their
+    /// resolvents on it, if this does not increase the number of clauses. Variables with more than
+    /// `occurrence_limit` occurrences are skipped, and so are variables with a resolvent longer
+    /// than `resolvent_limit`. The variables with the fewest possible resolvents are tried first,
+    /// until no variable can be eliminated anymore.
+    /// With `gates` a variable that is defined by a gate, see [`Gate`](crate::dpll::gates::Gate),
+    /// is eliminated with the resolvents of the gate clauses with the other clauses only.
+    /// Returns the number of eliminated variables.
+    pub(crate) fn eliminate_variables(&mut self) -> usize {
+        let mut eliminated = 0;
+        loop {
+            let mut candidates: Vec<(usize, usize)> = (0..self.variables.len())
+                .filter(|variable_index| self.variables[*variable_index].value == Value::Null && !self.is_frozen(*variable_index))
+                .filter_map(|variable_index| {
+                    let literal = (variable_index + 1) as i16;
+                    let positive = self.original_occurrences(literal).len();
+                    let negative = self.original_occurrences(-literal).len();
+                    let occurrences = positive + negative;
+                    (occurrences > 0 && occurrences <= self.preprocess.occurrence_limit)
+                        .then_some((positive * negative, variable_index))
+                })
+                .collect();
+            candidates.sort_unstable();
+            let before = eliminated;
+            for (_, variable_index) in candidates {
+                if self.eliminate_variable(variable_index) {
+                    eliminated += 1;
+                }
+                if self.result == FormulaResultType::Unsatisfiable {
+                    return eliminated;
+                }
+            }
+            if eliminated == before {
+                return eliminated;
+            }
+        }
+    }
+
+    /// Eliminate the variable if its resolvents are within the limits.
+    fn eliminate_variable(&mut self, variable_index: usize) -> bool {
+        let literal = (variable_index + 1) as i16;
+        let positive = self.original_occurrences(literal);
+        let negative = self.original_occurrences(-literal);
+        // an earlier elimination may have added clauses with this variable
+        if positive.len() + negative.len() > self.preprocess.occurrence_limit {
+            return false;
+        }
+        // with a gate the resolvents of two gate clauses are tautologies and the resolvents of two
+        // other clauses are implied by the rest
+        let gate = if self.preprocess.gates { self.find_gate(variable_index) } else { None };
+        let mut resolvents: Vec<(Vec<i16>, usize, usize)> = Vec::new();
+        for p in &positive {
+            for n in &negative {
+                if gate.as_ref().is_some_and(|gate| gate.clauses.contains(p) == gate.clauses.contains(n)) {
+                    continue;
+                }
+                let Some(resolvent) = resolve(&self.clauses[*p].literals, &self.clauses[*n].literals, literal) else {
+                    continue;
+                };
+                if resolvent.len() > self.preprocess.resolvent_limit {
+                    return false;
+                }
+                resolvents.push((resolvent, *p, *n));
+                if resolvents.len() > positive.len() + negative.len() {
+                    return false;
+                }
+            }
+        }
+        debug!(target: "eliminate_variables", "eliminating {} with {} resolvents for {} clauses", literal, resolvents.len(), positive.len() + negative.len());
+        if let Some(gate) = gate {
+            debug!(target: "eliminate_variables", "{} is defined by {:?} of {:?}", gate.output, gate.gate_type, &gate.inputs);
+            self.statistics.gate_eliminations += 1;
+        }
+        // the resolvents are derived before their antecedents are removed
+        for (resolvent, p, n) in resolvents {
+            self.proof_add(Clause::id(self.clauses.len()), &resolvent, &[Clause::id(p), Clause::id(n)]);
+            if let Some(result) = self.insert_clause(resolvent, ClauseType::Derived) {
+                self.result = result;
+            }
+        }
+        for clause_index in positive.iter().chain(negative.iter()) {
+            let witness = if positive.contains(clause_index) { literal } else { -literal };
+            self.push_reconstruction(witness, self.clauses[*clause_index].literals.clone());
+        }
+        // learned clauses with the variable are implied by the removed clauses, they are removed as well
+        let variable = &self.variables[variable_index];
+        let occurrences: Vec<usize> = variable.positive_occurrences.iter().chain(variable.negative_occurrences.iter()).copied().collect();
+        for clause_index in occurrences {
+            self.remove_clause(clause_index);
+        }
+        true
+    }
+}
@@ -0,0 +1,76 @@
+use crate::dpll::dpll::dpll;
+use crate::dpll::schemas::{Formula, FormulaResultType};
+use log::debug;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Iterator over all models of a formula, or over all distinct models projected onto some variables.
+///
+/// After every model a blocking clause with the negated literals of the (projected) model is added,
+/// so the next solve call has to find a different one. The blocking clauses are added in a scope
+/// of their own, which is closed when the iterator is dropped, so the formula can be used as
+/// before afterwards.
+#[derive(Debug)]
+pub struct ModelEnumerator<'a> {
+    formula: &'a mut Formula,
+    // variables of the models, starting with 1
+    projection: Vec<i16>,
+    timeout: Arc<AtomicBool>,
+    done: bool,
+}
+
+impl<'a> ModelEnumerator<'a> {
+    /// Enumerate the models projected onto the given variables, without projection all variables
+    /// of the formula are used. Returns an error if a projection variable is not part of the formula.
+    pub fn new(formula: &'a mut Formula, projection: Option<Vec<i16>>, timeout: Arc<AtomicBool>) -> Result<Self, String> {
+        let projection = projection.unwrap_or_else(|| (1..=formula.num_variables() as i16).collect());
+        if let Some(variable) = projection.iter().find(|variable| **variable <= 0 || **variable as usize > formula.num_variables()) {
+            return Err(format!("projection variable {} is not a variable of the formula", variable));
+        }
+        formula.push();
+        Ok(Self {
+            formula,
+            projection,
+            timeout,
+            done: false,
+        })
+    }
+
+    /// True if the enumeration was stopped by the timeout.
+    pub fn timed_out(&self) -> bool {
+        self.formula.result() == FormulaResultType::Timeout
+    }
+}
+
+impl Iterator for ModelEnumerator<'_> {
+    type Item = Vec<i16>;
+
+    fn next(&mut self) -> Option<Vec<i16>> {
+        if self.done {
+            return None;
+        }
+        dpll(self.formula, self.timeout.clone());
+        let model = match self.formula.model() {
+            Some(model) => model,
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+        let projected: Vec<i16> = self
+            .projection
+            .iter()
+            .map(|variable| model[(variable - 1) as usize])
+            .collect();
+        debug!(target: "enumerate", "found model: {:?}", &projected);
+        self.formula
+            .add_original_clause(projected.iter().map(|lit| -lit).collect());
+        Some(projected)
+    }
+}
+
+impl Drop for ModelEnumerator<'_> {
+    fn drop(&mut self) {
+        self.formula.pop();
+    }
+}
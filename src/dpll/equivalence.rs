@@ -0,0 +1,283 @@
+use crate::dpll::schemas::{Clause, ClauseType, Formula, FormulaResultType};
+use log::debug;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// the literals are the nodes of the implication graph, a negative literal follows its positive one
+pub(crate) fn node(literal: i16) -> usize {
+    2 * (literal.unsigned_abs() - 1) as usize + usize::from(literal < 0)
+}
+
+fn literal(node: usize) -> i16 {
+    let literal = (node / 2 + 1) as i16;
+    if node % 2 == 1 {
+        -literal
+    } else {
+        literal
+    }
+}
+
+// the LRAT hints of the path to every node
+type Paths = HashMap<usize, Vec<usize>>;
+
+/// The implication graph of the binary clauses, every edge has the index of its clause.
+pub(crate) struct ImplicationGraph {
+    pub(crate) edges: Vec<Vec<(usize, usize)>>,
+    // the strongly connected component of every node
+    component: Vec<usize>,
+}
+
+impl ImplicationGraph {
+    pub(crate) fn new(formula: &Formula) -> Self {
+        let mut edges = vec![Vec::new(); 2 * formula.variables.len()];
+        for (clause_index, clause) in formula.clauses.iter().enumerate() {
+            if let [a, b] = clause.literals[..] {
+                if !clause.deleted {
+                    edges[node(-a)].push((node(b), clause_index));
+                    edges[node(-b)].push((node(a), clause_index));
+                }
+            }
+        }
+        let component = vec![usize::MAX; edges.len()];
+        Self { edges, component }
+    }
+
+    /// Tarjan's algorithm without recursion, returns the strongly connected components with more
+    /// than one literal.
+    fn components(&mut self) -> Vec<Vec<usize>> {
+        let num_nodes = self.edges.len();
+        let mut index = vec![usize::MAX; num_nodes];
+        let mut low = vec![0; num_nodes];
+        let mut on_stack = vec![false; num_nodes];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+        for root in 0..num_nodes {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            // the nodes of the current path with the position of their next edge
+            let mut path = vec![(root, 0)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some((vertex, edge)) = path.last_mut() {
+                let vertex = *vertex;
+                if let Some((successor, _)) = self.edges[vertex].get(*edge).copied() {
+                    *edge += 1;
+                    if index[successor] == usize::MAX {
+                        index[successor] = next_index;
+                        low[successor] = next_index;
+                        next_index += 1;
+                        stack.push(successor);
+                        on_stack[successor] = true;
+                        path.push((successor, 0));
+                    } else if on_stack[successor] {
+                        low[vertex] = low[vertex].min(index[successor]);
+                    }
+                    continue;
+                }
+                path.pop();
+                if let Some((parent, _)) = path.last() {
+                    low[*parent] = low[*parent].min(low[vertex]);
+                }
+                if low[vertex] == index[vertex] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        self.component[member] = vertex;
+                        component.push(member);
+                        if member == vertex {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 {
+                        components.push(component);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    /// The clauses on a shortest path from the source to every other node of its component.
+    /// Under the source literal each clause of a path becomes unit in this order, so they are the
+    /// LRAT hints of a binary clause that follows from the path.
+    fn paths(&self, source: usize) -> Paths {
+        let mut parent: HashMap<usize, (usize, usize)> = HashMap::new();
+        let mut queue = VecDeque::from([source]);
+        while let Some(vertex) = queue.pop_front() {
+            for (successor, clause_index) in &self.edges[vertex] {
+                if self.component[*successor] == self.component[source] && *successor != source && !parent.contains_key(successor) {
+                    parent.insert(*successor, (vertex, *clause_index));
+                    queue.push_back(*successor);
+                }
+            }
+        }
+        parent
+            .keys()
+            .map(|target| {
+                let mut path = Vec::new();
+                let mut vertex = *target;
+                while let Some((previous, clause_index)) = parent.get(&vertex) {
+                    path.push(Clause::id(*clause_index));
+                    vertex = *previous;
+                }
+                path.reverse();
+                (*target, path)
+            })
+            .collect()
+    }
+}
+
+impl Formula {
+    /// Equivalent literal substitution.
+    ///
+    /// The literals of a strongly connected component of the binary implication graph are
+    /// equivalent. Every literal of a component is replaced by its representative in all clauses,
+    /// the representative is a frozen literal if there is one, otherwise the one with the
+    /// smallest variable. Frozen literals are never replaced. For the proof the equivalences are
+    /// derived as binary clauses first, the substituted clauses follow from them and the old ones.
+    /// The equivalences are kept on the reconstruction stack, so the substituted variables get the
+    /// value of their representative. If a literal is equivalent to its own negation the formula
+    /// is unsatisfiable. This is repeated as long as new equivalences are found.
+    /// Returns the number of substituted variables.
+    pub(crate) fn substitute_equivalences(&mut self) -> usize {
+        let mut substituted = 0;
+        loop {
+            let mut graph = ImplicationGraph::new(self);
+            let mut components = graph.components();
+            if components.is_empty() {
+                return substituted;
+            }
+            // a component and its negation are substituted together
+            let mut seen = vec![false; self.variables.len()];
+            components.retain(|component| {
+                let new = component.iter().all(|node| !seen[node / 2]);
+                for node in component {
+                    seen[node / 2] = true;
+                }
+                new
+            });
+            let mut substitutions: Vec<(i16, i16)> = Vec::new();
+            for component in components {
+                let literals: Vec<i16> = component.iter().map(|node| literal(*node)).collect();
+                if let Some(contradiction) = literals.iter().find(|lit| literals.contains(&-**lit)) {
+                    self.derive_contradiction(&graph, *contradiction);
+                    return substituted;
+                }
+                let representative = *literals
+                    .iter()
+                    .min_by_key(|lit| (!self.is_frozen((lit.unsigned_abs() - 1) as usize), lit.unsigned_abs()))
+                    .unwrap();
+                for lit in literals {
+                    if lit != representative && !self.is_frozen((lit.unsigned_abs() - 1) as usize) {
+                        substitutions.push((lit, representative));
+                    }
+                }
+            }
+            if substitutions.is_empty() {
+                return substituted;
+            }
+            substituted += substitutions.len();
+            self.substitute(&graph, substitutions);
+            if self.result == FormulaResultType::Unsatisfiable {
+                return substituted;
+            }
+        }
+    }
+
+    /// The literal implies its negation and the negation implies the literal.
+    fn derive_contradiction(&mut self, graph: &ImplicationGraph, literal: i16) {
+        debug!(target: "substitute_equivalences", "{} is equivalent to {}", literal, -literal);
+        let to_negation = graph.paths(node(literal)).remove(&node(-literal)).unwrap();
+        let from_negation = graph.paths(node(-literal)).remove(&node(literal)).unwrap();
+        let unit_index = self.clauses.len();
+        self.proof_add(Clause::id(unit_index), &[-literal], &to_negation);
+        self.insert_clause(vec![-literal], ClauseType::Learned);
+        let hints: Vec<usize> = std::iter::once(Clause::id(unit_index)).chain(from_negation).collect();
+        self.proof_add(Clause::id(self.clauses.len()), &[], &hints);
+        self.result = FormulaResultType::Unsatisfiable;
+    }
+
+    fn substitute(&mut self, graph: &ImplicationGraph, substitutions: Vec<(i16, i16)>) {
+        // for every substituted variable the representative of its positive literal and the two
+        // binary clauses of the equivalence, the first one implies the representative
+        let mut representatives: Vec<Option<(i16, usize, usize)>> = vec![None; self.variables.len()];
+        let mut equivalences = HashSet::new();
+        // the paths from the negated representative to the negated literals and from the
+        // representative to the literals of each component
+        let mut paths: HashMap<i16, (Paths, Paths)> = HashMap::new();
+        for (literal, representative) in substitutions {
+            debug!(target: "substitute_equivalences", "substitute {} by {}", literal, representative);
+            let (negated_paths, paths) = paths
+                .entry(representative)
+                .or_insert_with(|| (graph.paths(node(-representative)), graph.paths(node(representative))));
+            let implies = self.clauses.len();
+            self.proof_add(Clause::id(implies), &[-literal, representative], &negated_paths[&node(-literal)]);
+            self.insert_clause(vec![-literal, representative], ClauseType::Learned);
+            let implied = self.clauses.len();
+            self.proof_add(Clause::id(implied), &[literal, -representative], &paths[&node(literal)]);
+            self.insert_clause(vec![literal, -representative], ClauseType::Learned);
+            let variable_index = (literal.unsigned_abs() - 1) as usize;
+            representatives[variable_index] = Some(if literal > 0 {
+                (representative, implies, implied)
+            } else {
+                (-representative, implied, implies)
+            });
+            equivalences.extend([implies, implied]);
+            self.push_reconstruction(literal, vec![literal, -representative]);
+            self.push_reconstruction(-literal, vec![-literal, representative]);
+        }
+        let mut clause_indexes: Vec<usize> = representatives
+            .iter()
+            .enumerate()
+            .filter(|(_, representative)| representative.is_some())
+            .flat_map(|(variable_index, _)| {
+                let variable = &self.variables[variable_index];
+                variable.positive_occurrences.iter().chain(variable.negative_occurrences.iter()).copied()
+            })
+            .filter(|clause_index| !equivalences.contains(clause_index))
+            .collect();
+        clause_indexes.sort_unstable();
+        clause_indexes.dedup();
+        for clause_index in clause_indexes {
+            let mut literals = Vec::new();
+            let mut hints = Vec::new();
+            for lit in &self.clauses[clause_index].literals {
+                let substitute = match representatives[(lit.unsigned_abs() - 1) as usize] {
+                    // the substitute is false, so the equivalence makes the literal false
+                    Some((representative, implies, implied)) => {
+                        hints.push(Clause::id(if *lit > 0 { implies } else { implied }));
+                        if *lit > 0 {
+                            representative
+                        } else {
+                            -representative
+                        }
+                    }
+                    None => *lit,
+                };
+                if !literals.contains(&substitute) {
+                    literals.push(substitute);
+                }
+            }
+            let tautology = literals.iter().any(|lit| literals.contains(&-lit));
+            if !tautology {
+                hints.push(Clause::id(clause_index));
+                let clause_type = self.clauses[clause_index].clause_type;
+                self.proof_add(Clause::id(self.clauses.len()), &literals, &hints);
+                if let Some(result) = self.insert_clause(literals, clause_type) {
+                    self.result = result;
+                }
+            }
+            self.remove_clause(clause_index);
+        }
+        let mut equivalences: Vec<usize> = equivalences.into_iter().collect();
+        equivalences.sort_unstable();
+        for clause_index in equivalences {
+            self.remove_clause(clause_index);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::dpll::schemas::Formula;
+use std::collections::HashSet;
+
+// XOR gates are only searched up to this number of variables, they need 2^(n-1) clauses
+const XOR_LENGTH: usize = 5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GateType {
+    Equivalence,
+    And,
+    Ite,
+    Xor,
+}
+
+/// A gate definition in the original clauses, the output literal is a function of the inputs.
+///
+/// - Equivalence: the output is equal to its only input.
+/// - And: the output is the conjunction of the inputs, an or gate is an and gate with the
+///   negated output and inputs.
+/// - Ite: the inputs are condition, then and else, the output is the then input if the
+///   condition is true and the else input otherwise.
+/// - Xor: the output is the parity of the inputs.
+#[derive(Debug, Clone)]
+pub struct Gate {
+    pub gate_type: GateType,
+    pub output: i16,
+    pub inputs: Vec<i16>,
+    // the indexes of the clauses of the definition
+    pub(crate) clauses: Vec<usize>,
+}
+
+impl Formula {
+    /// The gates of all variables, a variable that is defined in several ways gets the first of
+    /// equivalence, and, ite and xor.
+    pub fn gates(&self) -> Vec<Gate> {
+        (0..self.variables.len()).filter_map(|variable_index| self.find_gate(variable_index)).collect()
+    }
+
+    /// The gate that defines the variable, if its original clauses contain one.
+    pub(crate) fn find_gate(&self, variable_index: usize) -> Option<Gate> {
+        let literal = (variable_index + 1) as i16;
+        self.find_equivalence(literal)
+            .or_else(|| self.find_and(literal))
+            .or_else(|| self.find_and(-literal))
+            .or_else(|| self.find_ite(literal))
+            .or_else(|| self.find_xor(literal))
+    }
+
+    /// The index of an original clause with exactly the given literals.
+    fn find_clause(&self, literals: &[i16]) -> Option<usize> {
+        self.original_occurrences(literals[0]).into_iter().find(|clause_index| {
+            let clause = &self.clauses[*clause_index].literals;
+            clause.len() == literals.len() && literals.iter().all(|lit| clause.contains(lit))
+        })
+    }
+
+    /// The original clauses with the literal and the given number of literals.
+    fn clauses_of_length(&self, literal: i16, length: usize) -> Vec<usize> {
+        self.original_occurrences(literal)
+            .into_iter()
+            .filter(|clause_index| self.clauses[*clause_index].literals.len() == length)
+            .collect()
+    }
+
+    fn find_equivalence(&self, output: i16) -> Option<Gate> {
+        self.clauses_of_length(-output, 2).into_iter().find_map(|implies| {
+            let input = *self.clauses[implies].literals.iter().find(|lit| **lit != -output)?;
+            let implied = self.find_clause(&[output, -input])?;
+            Some(Gate { gate_type: GateType::Equivalence, output, inputs: vec![input], clauses: vec![implies, implied] })
+        })
+    }
+
+    /// `o ∨ ¬a ∨ ¬b ∨ …` together with the binary clauses `¬o ∨ a`, `¬o ∨ b`, ….
+    fn find_and(&self, output: i16) -> Option<Gate> {
+        // the literals the output implies by a binary clause
+        let implied: Vec<(i16, usize)> = self
+            .clauses_of_length(-output, 2)
+            .into_iter()
+            .filter_map(|clause_index| {
+                let input = *self.clauses[clause_index].literals.iter().find(|lit| **lit != -output)?;
+                Some((input, clause_index))
+            })
+            .collect();
+        if implied.len() < 2 {
+            return None;
+        }
+        self.original_occurrences(output).into_iter().find_map(|long| {
+            let literals = &self.clauses[long].literals;
+            if literals.len() < 3 {
+                return None;
+            }
+            let mut clauses = vec![long];
+            let mut inputs = Vec::new();
+            for lit in literals.iter().filter(|lit| **lit != output) {
+                let (input, clause_index) = implied.iter().find(|(input, _)| *input == -lit)?;
+                inputs.push(*input);
+                clauses.push(*clause_index);
+            }
+            Some(Gate { gate_type: GateType::And, output, inputs, clauses })
+        })
+    }
+
+    /// `¬o ∨ ¬c ∨ t`, `¬o ∨ c ∨ e`, `o ∨ ¬c ∨ ¬t` and `o ∨ c ∨ ¬e`.
+    fn find_ite(&self, output: i16) -> Option<Gate> {
+        let ternary = self.clauses_of_length(-output, 3);
+        for then_clause in &ternary {
+            let literals = &self.clauses[*then_clause].literals;
+            for negated_condition in literals.iter().filter(|lit| **lit != -output) {
+                let condition = -negated_condition;
+                let then = *literals.iter().find(|lit| **lit != -output && *lit != negated_condition)?;
+                for else_clause in &ternary {
+                    let other = &self.clauses[*else_clause].literals;
+                    if !other.contains(&condition) {
+                        continue;
+                    }
+                    let Some(otherwise) = other.iter().copied().find(|lit| *lit != -output && *lit != condition) else {
+                        continue;
+                    };
+                    if otherwise.abs() == then.abs() || otherwise.abs() == condition.abs() {
+                        continue;
+                    }
+                    let (Some(then_back), Some(else_back)) =
+                        (self.find_clause(&[output, -condition, -then]), self.find_clause(&[output, condition, -otherwise]))
+                    else {
+                        continue;
+                    };
+                    return Some(Gate {
+                        gate_type: GateType::Ite,
+                        output,
+                        inputs: vec![condition, then, otherwise],
+                        clauses: vec![*then_clause, *else_clause, then_back, else_back],
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// All 2^(n-1) clauses over n variables with the same parity of negated literals. Each clause
+    /// forbids the assignment that falsifies it, so the parity of the true variables is fixed.
+    fn find_xor(&self, literal: i16) -> Option<Gate> {
+        let candidates: Vec<usize> = self.original_occurrences(literal).into_iter().chain(self.original_occurrences(-literal)).collect();
+        for clause_index in &candidates {
+            let literals = &self.clauses[*clause_index].literals;
+            if literals.len() < 3 || literals.len() > XOR_LENGTH {
+                continue;
+            }
+            let mut variables: Vec<i16> = literals.iter().map(|lit| lit.abs()).collect();
+            variables.sort_unstable();
+            let parity = literals.iter().filter(|lit| **lit < 0).count() % 2;
+            let mut signs = HashSet::new();
+            let mut clauses = Vec::new();
+            for other in &candidates {
+                let other_literals = &self.clauses[*other].literals;
+                if other_literals.len() != variables.len()
+                    || other_literals.iter().filter(|lit| **lit < 0).count() % 2 != parity
+                    || !other_literals.iter().all(|lit| variables.binary_search(&lit.abs()).is_ok())
+                {
+                    continue;
+                }
+                let sign: Vec<bool> = variables.iter().map(|variable| other_literals.contains(&-variable)).collect();
+                if signs.insert(sign) {
+                    clauses.push(*other);
+                }
+            }
+            if clauses.len() == 1 << (variables.len() - 1) {
+                // an odd number of negations forbids the odd assignments, so the parity is even
+                let output = if parity == 1 { literal.abs() } else { -literal.abs() };
+                let inputs = variables.into_iter().filter(|variable| *variable != literal.abs()).collect();
+                return Some(Gate { gate_type: GateType::Xor, output, inputs, clauses });
+            }
+        }
+        None
+    }
+}
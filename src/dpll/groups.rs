@@ -0,0 +1,104 @@
+use crate::dpll::schemas::Formula;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Clauses in groups like in the GCNF format of the MUS competition.
+/// Group 0 holds the hard clauses that are part of every subset. A plain CNF file has no hard
+/// clauses and every clause is its own group, numbered like the clauses of the DIMACS file.
+#[derive(Debug, Clone)]
+pub struct GroupedClauses {
+    pub num_variables: usize,
+    pub clauses: Vec<(usize, Vec<i16>)>,
+}
+
+impl GroupedClauses {
+    /// Read a `p cnf` or `p gcnf` file. GCNF clauses start with their group in braces: `{2} 1 -3 0`.
+    pub fn from_file(path: &PathBuf) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("can not read {:?}: {}", path, e))?;
+        let mut num_variables = 0;
+        let mut grouped = false;
+        let mut clauses = Vec::new();
+        let mut group = None;
+        let mut literals = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('c') || line.starts_with('%') {
+                continue;
+            }
+            if line.starts_with('p') {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                grouped = parts.get(1) == Some(&"gcnf");
+                num_variables = parts
+                    .get(2)
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| format!("invalid header: {}", line))?;
+                continue;
+            }
+            for part in line.split_whitespace() {
+                if let Some(number) = part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                    group = Some(number.parse::<usize>().map_err(|_| format!("invalid group: {}", part))?);
+                    continue;
+                }
+                let lit = part
+                    .parse::<i16>()
+                    .map_err(|_| format!("invalid literal: {}", part))?;
+                if lit != 0 {
+                    literals.push(lit);
+                    continue;
+                }
+                let clause_group = if grouped {
+                    group.take().ok_or_else(|| format!("clause without group: {:?}", literals))?
+                } else {
+                    clauses.len() + 1
+                };
+                clauses.push((clause_group, std::mem::take(&mut literals)));
+            }
+        }
+        // selectors are added after the variables, so the header must not be too small
+        let max_variable = clauses
+            .iter()
+            .flat_map(|(_, literals)| literals.iter())
+            .map(|lit| lit.unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0);
+        Ok(Self {
+            num_variables: num_variables.max(max_variable),
+            clauses,
+        })
+    }
+
+    /// The soft groups in ascending order with the indexes of their clauses.
+    pub fn groups(&self) -> BTreeMap<usize, Vec<usize>> {
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (index, (group, _)) in self.clauses.iter().enumerate() {
+            if *group != 0 {
+                groups.entry(*group).or_default().push(index);
+            }
+        }
+        groups
+    }
+
+    /// Build a formula where every soft group gets a selector literal. The clauses of a group
+    /// contain the negated selector, so they are only active if the selector is assumed.
+    /// Returns the formula and the groups by their selector, the selectors are internal literals
+    /// of the solver, so they are assumed with `set_internal_assumptions` and left out of the model.
+    pub fn with_selectors(&self) -> (Formula, HashMap<i16, usize>) {
+        let mut formula = Formula::new(self.num_variables);
+        let mut selectors = HashMap::new();
+        let mut selector_of_group = HashMap::new();
+        for group in self.groups().keys() {
+            let selector = formula.new_internal_variable();
+            selectors.insert(selector, *group);
+            selector_of_group.insert(*group, selector);
+        }
+        for (group, literals) in &self.clauses {
+            let mut clause: Vec<i16> = literals.iter().map(|lit| formula.internal_literal(*lit)).collect();
+            if *group != 0 {
+                clause.push(-selector_of_group[group]);
+            }
+            formula.add_internal_clause(clause);
+        }
+        (formula, selectors)
+    }
+}
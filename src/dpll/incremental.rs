@@ -0,0 +1,186 @@
+use crate::dpll::dpll::backtrack;
+use crate::dpll::schemas::{Clause, ClauseType, Formula, FormulaResultType, Value, Variable};
+use log::debug;
+
+impl Formula {
+    /// The number of variables of the formula, including the ones added after parsing.
+    /// The activation literals of the scopes and the variables added by the solver are not counted.
+    pub fn num_variables(&self) -> usize {
+        self.user_variables.len()
+    }
+
+    /// The result of the last solve call.
+    pub fn result(&self) -> FormulaResultType {
+        self.result
+    }
+
+    /// Add a fresh variable and return it as positive DIMACS literal.
+    pub fn new_variable(&mut self) -> i16 {
+        let index = self.add_variable();
+        self.user_variables.push(index);
+        self.user_variables.len() as i16
+    }
+
+    /// Add a fresh variable that is not visible to the caller, like an activation literal, and
+    /// return it as positive literal of the solver.
+    pub(crate) fn new_internal_variable(&mut self) -> i16 {
+        (self.add_variable() + 1) as i16
+    }
+
+    fn add_variable(&mut self) -> usize {
+        let index = self.variables.len();
+        self.variables.push(Variable::new());
+        self.variables_index.push((index, 0.0));
+        index
+    }
+
+    /// The literal of the solver for a literal of the caller, missing variables are created.
+    /// The numbers differ once a variable of the caller is added after an internal one.
+    pub(crate) fn internal_literal(&mut self, literal: i16) -> i16 {
+        while self.user_variables.len() < literal.unsigned_abs() as usize {
+            self.new_variable();
+        }
+        let variable = (self.user_variables[(literal.unsigned_abs() - 1) as usize] + 1) as i16;
+        if literal > 0 {
+            variable
+        } else {
+            -variable
+        }
+    }
+
+    /// The literal of the caller for a literal of the solver, None for internal variables.
+    fn user_literal(&self, literal: i16) -> Option<i16> {
+        let variable_index = (literal.unsigned_abs() - 1) as usize;
+        let position = self.user_variables.iter().position(|index| *index == variable_index)?;
+        Some(if literal > 0 { position as i16 + 1 } else { -(position as i16 + 1) })
+    }
+
+    /// Go back to the root level after a solve call, so that the formula can be changed and solved again.
+    /// Learned clauses, the variable scores and all assignments on depth 0 are kept.
+    /// A formula that is unsatisfiable without any decision stays unsatisfiable, if only the
+    /// assumptions of the open scopes failed it can be solved again.
+    pub fn reset(&mut self) {
+        if self.result == FormulaResultType::Unknown {
+            return;
+        }
+        debug!(target: "reset", "going back to the root level, last result: {:?}", self.result);
+        backtrack(self, 0);
+        self.units.clear();
+        if self.result != FormulaResultType::Unsatisfiable || !self.failed_assumptions.is_empty() {
+            self.result = FormulaResultType::Unknown;
+        }
+        self.failed_assumptions.clear();
+    }
+
+    /// Open a new scope. All original clauses that are added until the matching [`pop`](Formula::pop)
+    /// belong to this scope, they get the negated activation literal of the scope, which is
+    /// assumed to be true while the scope is open.
+    pub fn push(&mut self) {
+        self.reset();
+        let activation = self.new_internal_variable();
+        debug!(target: "push", "open scope {} with activation literal {}", self.scopes.len() + 1, activation);
+        self.scopes.push(activation);
+    }
+
+    /// Close the innermost scope and remove its clauses together with all learned clauses that
+    /// depend on them. Returns false if there is no open scope.
+    /// Clauses that depend on a scope contain its negated activation literal, because the
+    /// conflict analysis adds every decided assumption it reaches to the learned clause.
+    pub fn pop(&mut self) -> bool {
+        self.reset();
+        let activation = match self.scopes.pop() {
+            Some(activation) => activation,
+            None => return false,
+        };
+        let variable_index = (activation - 1) as usize;
+        for clause_index in self.variables[variable_index].negative_occurrences.clone() {
+            debug!(target: "pop", "remove clause: {:?}", self.clauses[clause_index]);
+            self.remove_clause(clause_index);
+        }
+        // the activation literal is disabled forever, this satisfies every clause that is left.
+        // No clause contains it negated any more, so the unit is a RAT lemma without candidates
+        self.proof_add_rat(Clause::id(self.clauses.len()), &[-activation], &[]);
+        self.insert_clause(vec![-activation], ClauseType::Original);
+        true
+    }
+
+    /// Assume the given literals to be true in all following solve calls, until other assumptions are set.
+    /// Variables that do not exist yet are created.
+    pub fn set_assumptions(&mut self, assumptions: Vec<i16>) {
+        let assumptions = assumptions.into_iter().map(|lit| self.internal_literal(lit)).collect();
+        self.set_internal_assumptions(assumptions);
+    }
+
+    /// Set assumptions on literals of the solver, like the selectors of the groups.
+    pub(crate) fn set_internal_assumptions(&mut self, assumptions: Vec<i16>) {
+        self.reset();
+        self.assumptions = assumptions;
+    }
+
+    pub(crate) fn is_assumption(&self, literal: i16) -> bool {
+        self.scopes.contains(&literal) || self.assumptions.contains(&literal)
+    }
+
+    /// The assumptions that are responsible for the last unsatisfiable result, the first one is
+    /// the assumption that was found false. The activation literals of the scopes are left out.
+    /// Empty if the formula is unsatisfiable without any assumption.
+    pub fn failed_assumptions(&self) -> Vec<i16> {
+        self.failed_assumptions.iter().filter_map(|lit| self.user_literal(*lit)).collect()
+    }
+
+    /// The number of open scopes.
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Add an original clause between two solve calls.
+    /// Variables that do not exist yet are created, the watched literals of the new clause are
+    /// chosen under the assignment of depth 0, so a clause that is unit there gets propagated
+    /// with the next solve call. Inside a scope the clause is removed again by [`pop`](Formula::pop).
+    pub fn add_original_clause(&mut self, literals: Vec<i16>) {
+        let literals = literals.into_iter().filter(|lit| *lit != 0).map(|lit| self.internal_literal(lit)).collect();
+        self.add_internal_clause(literals);
+    }
+
+    /// Add an original clause on literals of the solver, see [`add_original_clause`](Formula::add_original_clause).
+    pub(crate) fn add_internal_clause(&mut self, literals: Vec<i16>) {
+        self.reset();
+        let mut clause: Vec<i16> = Vec::with_capacity(literals.len());
+        for lit in literals {
+            if !clause.contains(&lit) {
+                clause.push(lit);
+            }
+        }
+        if let Some(activation) = self.scopes.last() {
+            // nothing contains the activation literal, so the clause is a RAT lemma on its negation,
+            // which has to be the first literal
+            clause.insert(0, -activation);
+            self.proof_add_rat(Clause::id(self.clauses.len()), &clause, &[]);
+        }
+        debug!(target: "add_original_clause", "adding original clause: {:?}", &clause);
+        if let Some(result) = self.insert_clause(clause, ClauseType::Original) {
+            self.result = result;
+        }
+    }
+
+    /// The model of the last solve call as DIMACS literals, if the formula was satisfiable.
+    /// The activation literals and the variables that were added by the solver are left out.
+    pub fn model(&self) -> Option<Vec<i16>> {
+        if self.result != FormulaResultType::Satisfiable {
+            return None;
+        }
+        Some(
+            self.user_variables
+                .iter()
+                .enumerate()
+                .map(|(position, index)| {
+                    if self.variables[*index].value == Value::True {
+                        (position + 1) as i16
+                    } else {
+                        -((position + 1) as i16)
+                    }
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,358 @@
+use crate::dpll::schemas::{Formula, FormulaResultType, Value};
+use clap::ValueEnum;
+use log::{debug, info, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum LocalSearchType {
+    WalkSat,
+    ProbSat,
+}
+
+/// The parameters of the local search.
+#[derive(Debug, Clone)]
+pub struct LocalSearchConfig {
+    pub algorithm: LocalSearchType,
+    /// WalkSAT: the probability to flip a random variable of the clause instead of the best one
+    pub noise: f64,
+    /// ProbSAT: the base of the polynomial break function, a variable with break count b is
+    /// picked with a probability proportional to (1 + b)^-cb
+    pub cb: f64,
+    /// Start again from a random assignment after this number of flips
+    pub restart_flips: usize,
+    /// Give up after this number of restarts, None to search until the timeout
+    pub max_tries: Option<usize>,
+    pub seed: u64,
+}
+
+/// Short local search runs inside CDCL. Every run starts from the current assignment and the saved
+/// phases of the free variables, the best assignment it finds becomes the saved phases, which are
+/// the polarities of the next decisions.
+#[derive(Debug, Clone)]
+pub struct LocalSearchPhases {
+    /// The number of conflicts between two runs
+    pub interval: usize,
+    /// The number of flips of one run
+    pub flips: usize,
+    pub config: LocalSearchConfig,
+}
+
+impl Default for LocalSearchConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: LocalSearchType::ProbSat,
+            noise: 0.567,
+            cb: 2.38,
+            restart_flips: 1_000_000,
+            max_tries: None,
+            seed: 1,
+        }
+    }
+}
+
+/// Stochastic local search over a complete assignment.
+///
+/// Every step picks a random falsified clause and flips one of its variables. The break count of
+/// a variable is the number of clauses that become false when it is flipped, it is kept up to date
+/// with every flip: a clause with one true literal counts for the variable of this literal.
+/// WalkSAT flips a variable without breaks if there is one, otherwise a random one with the
+/// probability `noise` and one with the fewest breaks else. ProbSAT picks the variable at random,
+/// weighted by its break count.
+#[derive(Debug)]
+pub struct LocalSearch {
+    clauses: Vec<Vec<i16>>,
+    // clause indexes by literal, the positive literal of variable i at 2i and the negative at 2i + 1
+    occurrences: Vec<Vec<usize>>,
+    values: Vec<bool>,
+    true_literals: Vec<usize>,
+    breaks: Vec<usize>,
+    // the falsified clauses and the position of every clause in this list
+    unsatisfied: Vec<usize>,
+    unsatisfied_position: Vec<Option<usize>>,
+    // the assignment with the fewest falsified clauses so far
+    best: Vec<bool>,
+    pub best_unsatisfied: usize,
+    config: LocalSearchConfig,
+    rng: StdRng,
+    pub flips: usize,
+    pub tries: usize,
+}
+
+fn literal_index(literal: i16) -> usize {
+    2 * (literal.unsigned_abs() as usize - 1) + usize::from(literal < 0)
+}
+
+impl LocalSearch {
+    pub fn new(num_variables: usize, clauses: Vec<Vec<i16>>, config: LocalSearchConfig) -> Self {
+        // tautologies are always satisfied, a clause has to have distinct literals for the counts
+        let clauses: Vec<Vec<i16>> = clauses
+            .into_iter()
+            .filter(|clause| !clause.iter().any(|lit| clause.contains(&-lit)))
+            .map(|mut clause| {
+                clause.sort_unstable();
+                clause.dedup();
+                clause
+            })
+            .collect();
+        let mut occurrences = vec![Vec::new(); 2 * num_variables];
+        for (clause_index, clause) in clauses.iter().enumerate() {
+            for lit in clause {
+                occurrences[literal_index(*lit)].push(clause_index);
+            }
+        }
+        let rng = StdRng::seed_from_u64(config.seed);
+        Self {
+            true_literals: vec![0; clauses.len()],
+            unsatisfied_position: vec![None; clauses.len()],
+            clauses,
+            occurrences,
+            values: vec![false; num_variables],
+            breaks: vec![0; num_variables],
+            unsatisfied: Vec::new(),
+            best: Vec::new(),
+            best_unsatisfied: usize::MAX,
+            config,
+            rng,
+            flips: 0,
+            tries: 0,
+        }
+    }
+
+    fn is_true(&self, literal: i16) -> bool {
+        self.values[literal.unsigned_abs() as usize - 1] == (literal > 0)
+    }
+
+    /// The only true literal of a clause with one true literal.
+    fn critical_variable(&self, clause_index: usize) -> usize {
+        let lit = self.clauses[clause_index].iter().find(|lit| self.is_true(**lit)).unwrap();
+        lit.unsigned_abs() as usize - 1
+    }
+
+    fn mark_unsatisfied(&mut self, clause_index: usize) {
+        self.unsatisfied_position[clause_index] = Some(self.unsatisfied.len());
+        self.unsatisfied.push(clause_index);
+    }
+
+    fn mark_satisfied(&mut self, clause_index: usize) {
+        let position = self.unsatisfied_position[clause_index].take().unwrap();
+        self.unsatisfied.swap_remove(position);
+        if let Some(moved) = self.unsatisfied.get(position) {
+            self.unsatisfied_position[*moved] = Some(position);
+        }
+    }
+
+    /// Start from the given assignment and compute the true literals and break counts from scratch.
+    fn initialize(&mut self, values: Vec<bool>) {
+        self.values = values;
+        self.breaks.iter_mut().for_each(|breaks| *breaks = 0);
+        self.unsatisfied.clear();
+        self.unsatisfied_position.iter_mut().for_each(|position| *position = None);
+        for clause_index in 0..self.clauses.len() {
+            let true_literals = self.clauses[clause_index].iter().filter(|lit| self.is_true(**lit)).count();
+            self.true_literals[clause_index] = true_literals;
+            match true_literals {
+                0 => self.mark_unsatisfied(clause_index),
+                1 => {
+                    let variable = self.critical_variable(clause_index);
+                    self.breaks[variable] += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn flip(&mut self, variable: usize) {
+        self.flips += 1;
+        self.values[variable] = !self.values[variable];
+        let now_true = if self.values[variable] { (variable + 1) as i16 } else { -((variable + 1) as i16) };
+        for position in 0..self.occurrences[literal_index(now_true)].len() {
+            let clause_index = self.occurrences[literal_index(now_true)][position];
+            self.true_literals[clause_index] += 1;
+            match self.true_literals[clause_index] {
+                1 => {
+                    self.mark_satisfied(clause_index);
+                    self.breaks[variable] += 1;
+                }
+                2 => {
+                    // the other true literal is not critical anymore
+                    let other = self.clauses[clause_index]
+                        .iter()
+                        .find(|lit| self.is_true(**lit) && lit.unsigned_abs() as usize - 1 != variable)
+                        .unwrap();
+                    self.breaks[other.unsigned_abs() as usize - 1] -= 1;
+                }
+                _ => {}
+            }
+        }
+        for position in 0..self.occurrences[literal_index(-now_true)].len() {
+            let clause_index = self.occurrences[literal_index(-now_true)][position];
+            self.true_literals[clause_index] -= 1;
+            match self.true_literals[clause_index] {
+                0 => {
+                    self.mark_unsatisfied(clause_index);
+                    self.breaks[variable] -= 1;
+                }
+                1 => {
+                    let critical = self.critical_variable(clause_index);
+                    self.breaks[critical] += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn pick_walksat(&mut self, clause: &[i16]) -> usize {
+        let variables: Vec<usize> = clause.iter().map(|lit| lit.unsigned_abs() as usize - 1).collect();
+        let fewest = variables.iter().map(|variable| self.breaks[*variable]).min().unwrap();
+        if fewest > 0 && self.rng.gen_bool(self.config.noise) {
+            return variables[self.rng.gen_range(0..variables.len())];
+        }
+        let best: Vec<usize> = variables.into_iter().filter(|variable| self.breaks[*variable] == fewest).collect();
+        best[self.rng.gen_range(0..best.len())]
+    }
+
+    fn pick_probsat(&mut self, clause: &[i16]) -> usize {
+        let weights: Vec<f64> = clause
+            .iter()
+            .map(|lit| (1.0 + self.breaks[lit.unsigned_abs() as usize - 1] as f64).powf(-self.config.cb))
+            .collect();
+        let mut threshold = self.rng.gen::<f64>() * weights.iter().sum::<f64>();
+        for (lit, weight) in clause.iter().zip(&weights) {
+            if threshold < *weight {
+                return lit.unsigned_abs() as usize - 1;
+            }
+            threshold -= weight;
+        }
+        clause.last().unwrap().unsigned_abs() as usize - 1
+    }
+
+    /// Search a model, starting from the given assignment in the first try and from random ones
+    /// after every restart. Returns None on timeout or after the last try.
+    pub fn search(&mut self, initial: Option<Vec<bool>>, timeout: Arc<AtomicBool>) -> Option<Vec<bool>> {
+        if self.clauses.iter().any(|clause| clause.is_empty()) {
+            return None;
+        }
+        let mut initial = initial;
+        loop {
+            if self.config.max_tries.is_some_and(|tries| self.tries >= tries) {
+                return None;
+            }
+            self.tries += 1;
+            let values = match initial.take() {
+                Some(values) => values,
+                None => (0..self.values.len()).map(|_| self.rng.gen::<bool>()).collect(),
+            };
+            self.initialize(values);
+            debug!(target: "local_search", "try {} starts with {} false clauses", self.tries, self.unsatisfied.len());
+            for step in 0..self.config.restart_flips {
+                if self.unsatisfied.len() < self.best_unsatisfied {
+                    self.best_unsatisfied = self.unsatisfied.len();
+                    self.best.clone_from(&self.values);
+                }
+                if self.unsatisfied.is_empty() {
+                    info!(target: "local_search", "found a model after {} flips in {} tries", self.flips, self.tries);
+                    return Some(self.values.clone());
+                }
+                if step % 1024 == 0 && timeout.load(Ordering::SeqCst) {
+                    return None;
+                }
+                let clause_index = self.unsatisfied[self.rng.gen_range(0..self.unsatisfied.len())];
+                let clause = self.clauses[clause_index].clone();
+                let variable = match self.config.algorithm {
+                    LocalSearchType::WalkSat => self.pick_walksat(&clause),
+                    LocalSearchType::ProbSat => self.pick_probsat(&clause),
+                };
+                self.flip(variable);
+            }
+            if self.unsatisfied.len() < self.best_unsatisfied {
+                self.best_unsatisfied = self.unsatisfied.len();
+                self.best.clone_from(&self.values);
+            }
+            if self.unsatisfied.is_empty() {
+                return Some(self.values.clone());
+            }
+        }
+    }
+
+    /// The assignment with the fewest falsified clauses of all tries, empty before the first try.
+    pub fn best(&self) -> &[bool] {
+        &self.best
+    }
+}
+
+/// Solve the formula with local search. It can only find models, so the result is satisfiable
+/// with the model as assignment of the variables, or it stays unknown (timeout on timeout).
+/// The assignment is not on the assignment stack, the formula can not be solved again afterwards.
+pub fn local_search(formula: &mut Formula, config: LocalSearchConfig, timeout: Arc<AtomicBool>) {
+    if !formula.xor_constraints().is_empty() {
+        warn!(target: "local_search", "local search does not support xor constraints");
+        return;
+    }
+    formula.preprocess();
+    if formula.result == FormulaResultType::Unsatisfiable {
+        return;
+    }
+    let clauses: Vec<Vec<i16>> = formula
+        .clauses()
+        .iter()
+        .filter(|clause| !clause.deleted)
+        .map(|clause| clause.literals().to_vec())
+        .collect();
+    let mut search = LocalSearch::new(formula.variables.len(), clauses, config);
+    match search.search(None, timeout.clone()) {
+        Some(values) => {
+            for (variable, value) in formula.variables.iter_mut().zip(values) {
+                variable.value = if value { Value::True } else { Value::False };
+            }
+            formula.reconstruct_model();
+            formula.result = FormulaResultType::Satisfiable;
+        }
+        None if timeout.load(Ordering::SeqCst) => formula.result = FormulaResultType::Timeout,
+        None => formula.result = FormulaResultType::Unknown,
+    }
+}
+
+/// Run a short local search from the current assignment, unassigned variables start with their
+/// saved phase. The best assignment of the run becomes the saved phases, so the next decisions
+/// follow it. The search is bounded by the flips of [`LocalSearchPhases`].
+pub(crate) fn update_phases(formula: &mut Formula, timeout: Arc<AtomicBool>) {
+    let Some(phases) = formula.local_search_phases.clone() else {
+        return;
+    };
+    if !formula.xor_constraints().is_empty() {
+        return;
+    }
+    let clauses: Vec<Vec<i16>> = formula
+        .clauses()
+        .iter()
+        .filter(|clause| !clause.deleted)
+        .map(|clause| clause.literals().to_vec())
+        .collect();
+    let initial = formula
+        .variables
+        .iter()
+        .map(|variable| match variable.value {
+            Value::Null => variable.phase,
+            value => value == Value::True,
+        })
+        .collect();
+    let config = LocalSearchConfig {
+        restart_flips: phases.flips,
+        max_tries: Some(1),
+        // every run gets other random choices
+        seed: phases.config.seed.wrapping_add(formula.conflicts as u64),
+        ..phases.config
+    };
+    let mut search = LocalSearch::new(formula.variables.len(), clauses, config);
+    search.search(Some(initial), timeout);
+    if search.best().is_empty() {
+        return;
+    }
+    debug!(target: "local_search", "phases after {} conflicts with {} false clauses", formula.conflicts, search.best_unsatisfied);
+    for (variable, phase) in formula.variables.iter_mut().zip(search.best()) {
+        variable.phase = *phase;
+    }
+}
@@ -0,0 +1,118 @@
+use crate::dpll::dpll::dpll;
+use crate::dpll::groups::GroupedClauses;
+use crate::dpll::schemas::{Formula, FormulaResultType};
+use log::debug;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Enumeration of the minimal correction subsets of the soft groups, the hard clauses of group 0
+/// are never removed.
+///
+/// Every MCS is the complement of a maximal satisfiable subset: starting with the groups that
+/// are satisfied by a model, every other group is tried once and added if the subset stays
+/// satisfiable, the groups of later models are added as well. A found MCS is blocked by a hard
+/// clause that requires at least one of its groups, so no superset of it is found again.
+/// The MCSes are returned one by one as they are found.
+#[derive(Debug)]
+pub struct McsEnumerator<'a> {
+    clauses: &'a GroupedClauses,
+    formula: Formula,
+    selector_of_group: HashMap<usize, i16>,
+    timeout: Arc<AtomicBool>,
+    done: bool,
+    timed_out: bool,
+    pub solve_calls: usize,
+}
+
+impl<'a> McsEnumerator<'a> {
+    pub fn new(clauses: &'a GroupedClauses, timeout: Arc<AtomicBool>) -> Self {
+        let (formula, selectors) = clauses.with_selectors();
+        Self {
+            clauses,
+            formula,
+            selector_of_group: selectors.iter().map(|(selector, group)| (*group, *selector)).collect(),
+            timeout,
+            done: false,
+            timed_out: false,
+            solve_calls: 0,
+        }
+    }
+
+    /// True if all MCSes were found, false if the enumeration was stopped by the timeout.
+    pub fn is_complete(&self) -> bool {
+        self.done && !self.timed_out
+    }
+
+    fn solve(&mut self, groups: &BTreeSet<usize>) -> FormulaResultType {
+        self.solve_calls += 1;
+        let assumptions = groups.iter().map(|group| self.selector_of_group[group]).collect();
+        self.formula.set_internal_assumptions(assumptions);
+        dpll(&mut self.formula, self.timeout.clone());
+        if self.formula.result() == FormulaResultType::Timeout {
+            self.timed_out = true;
+            self.done = true;
+        }
+        self.formula.result()
+    }
+
+    /// The soft groups whose clauses are all satisfied by the model of the last solve call.
+    fn satisfied_groups(&self) -> BTreeSet<usize> {
+        let model = self.formula.model().unwrap();
+        let mut falsified = BTreeSet::new();
+        for (group, literals) in &self.clauses.clauses {
+            if !literals.iter().any(|lit| model[(lit.unsigned_abs() - 1) as usize] == *lit) {
+                falsified.insert(*group);
+            }
+        }
+        self.selector_of_group
+            .keys()
+            .filter(|group| !falsified.contains(group))
+            .copied()
+            .collect()
+    }
+}
+
+impl Iterator for McsEnumerator<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.done || self.timeout.load(Ordering::SeqCst) {
+            self.timed_out |= !self.done;
+            self.done = true;
+            return None;
+        }
+        if self.solve(&BTreeSet::new()) != FormulaResultType::Satisfiable {
+            // the hard clauses and the blocking clauses are unsatisfiable, there is no MCS left
+            self.done = true;
+            return None;
+        }
+        let mut satisfied = self.satisfied_groups();
+        let mut groups: Vec<usize> = self.selector_of_group.keys().copied().collect();
+        groups.sort_unstable();
+        let mut mcs = Vec::new();
+        for group in groups {
+            if satisfied.contains(&group) {
+                continue;
+            }
+            satisfied.insert(group);
+            match self.solve(&satisfied) {
+                FormulaResultType::Satisfiable => satisfied.extend(self.satisfied_groups()),
+                FormulaResultType::Timeout => return None,
+                _ => {
+                    satisfied.remove(&group);
+                    mcs.push(group);
+                }
+            }
+        }
+        debug!(target: "mcs", "found mcs: {:?}", &mcs);
+        if mcs.is_empty() {
+            // the whole formula is satisfiable, the empty set is the only MCS
+            self.done = true;
+        } else {
+            let blocking = mcs.iter().map(|group| self.selector_of_group[group]).collect();
+            self.formula.add_internal_clause(blocking);
+        }
+        Some(mcs)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::dpll::dpll::dpll;
+use crate::dpll::groups::GroupedClauses;
+use crate::dpll::schemas::{Formula, FormulaResultType};
+use log::{debug, info};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+enum SubsetResult {
+    // the values of the variables, starting with variable 1 at index 0
+    Satisfiable(Vec<bool>),
+    // the groups of the failed selectors
+    Unsatisfiable(Vec<usize>),
+    // the solve call stopped without an answer, like by the timeout
+    Unknown(FormulaResultType),
+}
+
+/// Deletion based extraction of a minimal unsatisfiable subset of groups.
+///
+/// Every group is removed once by solving without its selector. If the rest is still unsatisfiable
+/// the groups of the failed selectors are kept (clause-set refinement), otherwise the group is
+/// critical and stays in the subset. The model of a satisfiable call is used to find further
+/// critical groups without solving: flipping a variable that only falsifies one other group
+/// shows that this group is critical too (model rotation).
+#[derive(Debug)]
+pub struct MusExtractor<'a> {
+    clauses: &'a GroupedClauses,
+    groups: BTreeMap<usize, Vec<usize>>,
+    formula: Formula,
+    selectors: HashMap<i16, usize>,
+    selector_of_group: HashMap<usize, i16>,
+    timeout: Arc<AtomicBool>,
+    pub solve_calls: usize,
+    pub rotated_groups: usize,
+}
+
+impl<'a> MusExtractor<'a> {
+    pub fn new(clauses: &'a GroupedClauses, timeout: Arc<AtomicBool>) -> Self {
+        let (formula, selectors) = clauses.with_selectors();
+        let selector_of_group = selectors.iter().map(|(selector, group)| (*group, *selector)).collect();
+        Self {
+            clauses,
+            groups: clauses.groups(),
+            formula,
+            selectors,
+            selector_of_group,
+            timeout,
+            solve_calls: 0,
+            rotated_groups: 0,
+        }
+    }
+
+    fn solve(&mut self, groups: &BTreeSet<usize>) -> SubsetResult {
+        self.solve_calls += 1;
+        let assumptions = groups.iter().map(|group| self.selector_of_group[group]).collect();
+        self.formula.set_internal_assumptions(assumptions);
+        dpll(&mut self.formula, self.timeout.clone());
+        match self.formula.result() {
+            FormulaResultType::Satisfiable => {
+                let model = self.formula.model().unwrap();
+                SubsetResult::Satisfiable(model.iter().map(|lit| *lit > 0).collect())
+            }
+            FormulaResultType::Unsatisfiable => SubsetResult::Unsatisfiable(
+                self.formula
+                    .failed_assumptions
+                    .iter()
+                    .filter_map(|lit| self.selectors.get(lit).copied())
+                    .collect(),
+            ),
+            result => SubsetResult::Unknown(result),
+        }
+    }
+
+    fn is_satisfied(literals: &[i16], model: &[bool]) -> bool {
+        literals
+            .iter()
+            .any(|lit| model[(lit.unsigned_abs() - 1) as usize] == (*lit > 0))
+    }
+
+    /// The only group of the subset that is falsified by the model, None if the model falsifies a
+    /// hard clause or more than one group.
+    fn only_falsified_group(&self, model: &[bool], subset: &BTreeSet<usize>) -> Option<usize> {
+        let mut falsified = None;
+        for (group, literals) in &self.clauses.clauses {
+            if *group != 0 && !subset.contains(group) || Self::is_satisfied(literals, model) {
+                continue;
+            }
+            match falsified {
+                _ if *group == 0 => return None,
+                Some(other) if other != *group => return None,
+                _ => falsified = Some(*group),
+            }
+        }
+        falsified
+    }
+
+    fn rotate_model(&mut self, group: usize, model: Vec<bool>, subset: &BTreeSet<usize>, critical: &mut BTreeSet<usize>) {
+        let mut stack = vec![(group, model)];
+        while let Some((group, model)) = stack.pop() {
+            for clause_index in &self.groups[&group] {
+                let literals = &self.clauses.clauses[*clause_index].1;
+                if Self::is_satisfied(literals, &model) {
+                    continue;
+                }
+                for lit in literals {
+                    let mut flipped = model.clone();
+                    let variable_index = (lit.unsigned_abs() - 1) as usize;
+                    flipped[variable_index] = !flipped[variable_index];
+                    if let Some(next) = self.only_falsified_group(&flipped, subset) {
+                        if critical.insert(next) {
+                            debug!(target: "rotate_model", "group {} is critical by flipping {}", next, lit);
+                            self.rotated_groups += 1;
+                            stack.push((next, flipped));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// A minimal unsatisfiable subset of the soft groups, the hard clauses are part of every subset.
+    /// Returns None if the formula is satisfiable, and the result of the solve call as error if
+    /// one of them gave no answer, like after the timeout.
+    pub fn find_mus(&mut self) -> Result<Option<Vec<usize>>, FormulaResultType> {
+        let all_groups: BTreeSet<usize> = self.groups.keys().copied().collect();
+        let mut subset: BTreeSet<usize> = match self.solve(&all_groups) {
+            SubsetResult::Satisfiable(_) => return Ok(None),
+            SubsetResult::Unsatisfiable(failed) => failed.into_iter().collect(),
+            SubsetResult::Unknown(result) => return Err(result),
+        };
+        info!(target: "find_mus", "starting with {} of {} groups", subset.len(), all_groups.len());
+        let mut critical = BTreeSet::new();
+        while let Some(group) = subset.iter().copied().find(|group| !critical.contains(group)) {
+            let mut candidates = subset.clone();
+            candidates.remove(&group);
+            match self.solve(&candidates) {
+                SubsetResult::Unsatisfiable(failed) => {
+                    // the critical groups are part of every unsatisfiable subset of the candidates
+                    subset = failed.into_iter().collect();
+                    debug!(target: "find_mus", "removed group {}, {} groups left", group, subset.len());
+                }
+                SubsetResult::Satisfiable(model) => {
+                    critical.insert(group);
+                    debug!(target: "find_mus", "group {} is critical", group);
+                    self.rotate_model(group, model, &subset, &mut critical);
+                }
+                // a group that is not known to be removable can not be removed
+                SubsetResult::Unknown(result) => return Err(result),
+            }
+        }
+        Ok(Some(subset.into_iter().collect()))
+    }
+}
@@ -0,0 +1,173 @@
+use crate::dpll::schemas::{Formula, FormulaResultType, Value};
+use log::{debug, info, warn};
+use std::time::Duration;
+
+/// The simplifications that run before the search, all of them are disabled by default.
+#[derive(Debug, Clone)]
+pub struct PreprocessConfig {
+    /// Substitution of the literals that are equivalent by the binary clauses
+    pub substitution: bool,
+    /// Subsumption and self-subsuming resolution, also on the learned clauses during the search
+    pub subsumption: bool,
+    /// Bounded variable elimination by clause distribution
+    pub elimination: bool,
+    /// Variables with more occurrences in the original clauses are not eliminated
+    pub occurrence_limit: usize,
+    /// The elimination of a variable is given up if one of its resolvents has more literals
+    pub resolvent_limit: usize,
+    /// Gate-based elimination, a variable that is defined by a gate only gets the resolvents of
+    /// the gate clauses with the other clauses
+    pub gates: bool,
+    /// Failed literal probing on the root level, at the start of the search and at every round of inprocessing
+    pub probing: bool,
+    /// The time limit of one probing round
+    pub probe_time: Duration,
+    /// Hyper-binary resolution during the probing
+    pub hyper_binary: bool,
+    /// Blocked clause elimination
+    pub blocked: bool,
+    /// Covered clause elimination, which extends the blocked clause elimination
+    pub covered: bool,
+    /// Bounded variable addition, the new variables are left out of the model
+    pub addition: bool,
+    /// Vivification of the learned clauses during the search
+    pub vivification: bool,
+    /// Vivify the original clauses as well
+    pub vivify_original: bool,
+    /// The effort of a vivification round in percent of the assignments of the search since the last one
+    pub vivify_effort: usize,
+    /// Transitive reduction of the binary implication graph during the search
+    pub transitive_reduction: bool,
+    /// The number of conflicts between two rounds of simplifications during the search
+    pub inprocess_interval: usize,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            substitution: false,
+            subsumption: false,
+            elimination: false,
+            occurrence_limit: 20,
+            resolvent_limit: 20,
+            gates: false,
+            probing: false,
+            probe_time: Duration::from_millis(100),
+            hyper_binary: false,
+            blocked: false,
+            covered: false,
+            addition: false,
+            vivification: false,
+            vivify_original: false,
+            vivify_effort: 10,
+            transitive_reduction: false,
+            inprocess_interval: 2000,
+        }
+    }
+}
+
+impl Formula {
+    /// Simplify the original clauses with the enabled techniques of the [`PreprocessConfig`],
+    /// this is done by the first solve call. Every added and removed clause is written to the proof.
+    /// Removed clauses that are needed to extend a model of the simplified formula to the removed
+    /// variables are kept on the reconstruction stack, see [`reconstruct_model`](Formula::reconstruct_model).
+    /// Clauses that are added afterwards must not contain eliminated variables or the witnesses
+    /// of removed clauses.
+    pub(crate) fn preprocess(&mut self) {
+        if self.preprocessed {
+            return;
+        }
+        self.preprocessed = true;
+        if self.result == FormulaResultType::Unsatisfiable {
+            return;
+        }
+        if !self.assigment_stack.is_empty() {
+            warn!(target: "preprocess", "the formula was solved before, it is not preprocessed");
+            return;
+        }
+        self.remove_tautologies();
+        if self.preprocess.substitution {
+            let substituted = self.substitute_equivalences();
+            info!(target: "preprocess", "substituted {} equivalent variables", substituted);
+        }
+        if self.preprocess.subsumption && self.result != FormulaResultType::Unsatisfiable {
+            let counts = self.subsume_all();
+            self.log_subsumption(counts);
+        }
+        if self.preprocess.elimination && self.result != FormulaResultType::Unsatisfiable {
+            let eliminated = self.eliminate_variables();
+            info!(target: "preprocess", "eliminated {} variables", eliminated);
+            if self.preprocess.subsumption && self.result != FormulaResultType::Unsatisfiable {
+                let counts = self.subsume_all();
+                self.log_subsumption(counts);
+            }
+        }
+        if (self.preprocess.blocked || self.preprocess.covered) && self.result != FormulaResultType::Unsatisfiable {
+            let removed = self.eliminate_blocked_clauses();
+            info!(target: "preprocess", "removed {} blocked clauses", removed);
+        }
+        if self.preprocess.addition && self.result != FormulaResultType::Unsatisfiable {
+            let added = self.add_variables();
+            info!(target: "preprocess", "added {} variables", added);
+        }
+    }
+
+    /// The simplifications of the learned clauses during the search, they run every
+    /// `inprocess_interval` conflicts when there is no unit left to propagate.
+    pub(crate) fn inprocess(&mut self) {
+        if self.preprocess.subsumption {
+            let (subsumed, strengthened) = self.subsume_learned();
+            debug!(target: "inprocess", "subsumed {} and strengthened {} learned clauses", subsumed, strengthened);
+        }
+        if self.preprocess.transitive_reduction {
+            let removed = self.reduce_transitive();
+            self.statistics.transitive_reductions += removed;
+            debug!(target: "inprocess", "removed {} transitive binary clauses", removed);
+        }
+    }
+
+    /// Whether any simplification is done during the search.
+    pub(crate) fn inprocessing(&self) -> bool {
+        self.preprocess.subsumption || self.preprocess.probing || self.preprocess.vivification || self.preprocess.transitive_reduction
+    }
+
+    fn log_subsumption(&self, (subsumed, strengthened): (usize, usize)) {
+        info!(target: "preprocess", "subsumed {} and strengthened {} clauses", subsumed, strengthened);
+    }
+
+    /// Tautologies are always satisfied, they are removed so that no technique has to handle them.
+    fn remove_tautologies(&mut self) {
+        for clause_index in 0..self.clauses.len() {
+            let literals = &self.clauses[clause_index].literals;
+            if !self.clauses[clause_index].deleted && literals.iter().any(|lit| literals.contains(&-lit)) {
+                self.remove_clause(clause_index);
+            }
+        }
+    }
+
+    /// Assumptions, activation literals and the variables of XOR constraints keep all their
+    /// clauses, they can not be removed by preprocessing.
+    pub(crate) fn is_frozen(&self, variable_index: usize) -> bool {
+        let literal = (variable_index + 1) as i16;
+        self.is_assumption(literal) || self.is_assumption(-literal) || self.is_xor_variable(variable_index)
+    }
+
+    /// Push a removed clause to the reconstruction stack. The witness is a literal of the clause
+    /// that can be made true to satisfy it, without falsifying the clauses that were removed before.
+    pub(crate) fn push_reconstruction(&mut self, witness: i16, literals: Vec<i16>) {
+        self.reconstruction.push((witness, literals));
+    }
+
+    /// Extend the model of the simplified formula to a model of the original one. The removed
+    /// clauses are visited in reverse order, every clause that is false gets its witness flipped.
+    pub(crate) fn reconstruct_model(&mut self) {
+        for index in (0..self.reconstruction.len()).rev() {
+            let (witness, literals) = &self.reconstruction[index];
+            if literals.iter().any(|lit| self.literal_value(*lit) == Value::True) {
+                continue;
+            }
+            let value = if *witness > 0 { Value::True } else { Value::False };
+            self.variables[(witness.unsigned_abs() - 1) as usize].value = value;
+        }
+    }
+}
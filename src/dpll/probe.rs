@@ -0,0 +1,343 @@
+use crate::dpll::dpll::unit_propagation;
+use crate::dpll::schemas::{Clause, ClauseType, Formula, FormulaResultType, Value};
+use log::debug;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+// the number of hyper-binary resolvents that are added at most for one probe
+const HYPER_BINARY_LIMIT: usize = 1000;
+
+/// An assignment on top of the root level. The propagation goes over the occurrence lists instead
+/// of the watched literals, so the search state is never touched.
+pub(crate) struct Prober {
+    values: Vec<Value>,
+    reasons: Vec<Option<usize>>,
+    pub(crate) trail: Vec<i16>,
+    // the position of the next literal of the trail to propagate
+    propagated: usize,
+    // a clause that does not take part in the propagation
+    pub(crate) ignored: Option<usize>,
+    // the number of assigned literals, to bound the effort
+    pub(crate) ticks: usize,
+}
+
+impl Prober {
+    pub(crate) fn new(num_variables: usize) -> Self {
+        Self {
+            values: vec![Value::Null; num_variables],
+            reasons: vec![None; num_variables],
+            trail: Vec::new(),
+            propagated: 0,
+            ignored: None,
+            ticks: 0,
+        }
+    }
+
+    pub(crate) fn value(&self, formula: &Formula, literal: i16) -> Value {
+        match formula.literal_value(literal) {
+            Value::Null => match (self.values[(literal.unsigned_abs() - 1) as usize], literal > 0) {
+                (Value::Null, _) => Value::Null,
+                (Value::True, true) | (Value::False, false) => Value::True,
+                _ => Value::False,
+            },
+            value => value,
+        }
+    }
+
+    fn assign(&mut self, literal: i16, reason: Option<usize>) {
+        let variable_index = (literal.unsigned_abs() - 1) as usize;
+        self.values[variable_index] = if literal > 0 { Value::True } else { Value::False };
+        self.reasons[variable_index] = reason;
+        self.trail.push(literal);
+        self.ticks += 1;
+    }
+
+    /// Assign the literal and propagate it, returns the conflicting clause if there is one.
+    pub(crate) fn propagate(&mut self, formula: &Formula, literal: i16) -> Result<(), usize> {
+        self.assign(literal, None);
+        while self.propagated < self.trail.len() {
+            let falsified = -self.trail[self.propagated];
+            self.propagated += 1;
+            let variable = &formula.variables[(falsified.unsigned_abs() - 1) as usize];
+            let occurrences = if falsified > 0 { &variable.positive_occurrences } else { &variable.negative_occurrences };
+            for clause_index in occurrences {
+                let clause = &formula.clauses[*clause_index];
+                if clause.deleted || self.ignored == Some(*clause_index) {
+                    continue;
+                }
+                let mut free = None;
+                let mut free_count = 0;
+                let mut satisfied = false;
+                for lit in &clause.literals {
+                    match self.value(formula, *lit) {
+                        Value::True => {
+                            satisfied = true;
+                            break;
+                        }
+                        Value::Null => {
+                            free = Some(*lit);
+                            free_count += 1;
+                        }
+                        Value::False => {}
+                    }
+                }
+                match (satisfied, free_count) {
+                    (false, 0) => return Err(*clause_index),
+                    (false, 1) => self.assign(free.unwrap(), Some(*clause_index)),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn undo(&mut self) {
+        self.propagated = 0;
+        for literal in self.trail.drain(..) {
+            let variable_index = (literal.unsigned_abs() - 1) as usize;
+            self.values[variable_index] = Value::Null;
+            self.reasons[variable_index] = None;
+        }
+    }
+
+    /// The LRAT hints for the given literals: the reasons of all assignments they depend on in
+    /// the order of their propagation, the root level first.
+    pub(crate) fn hints(&self, formula: &Formula, literals: &[i16]) -> Vec<usize> {
+        let mut position = vec![(0, 0); formula.variables.len()];
+        for (index, assignment) in formula.assigment_stack.iter().enumerate() {
+            position[assignment.variable_index] = (0, index);
+        }
+        for (index, literal) in self.trail.iter().enumerate() {
+            position[(literal.unsigned_abs() - 1) as usize] = (1, index);
+        }
+        let mut visited = vec![false; formula.variables.len()];
+        let mut stack: Vec<i16> = literals.to_vec();
+        let mut reasons = Vec::new();
+        while let Some(literal) = stack.pop() {
+            let variable_index = (literal.unsigned_abs() - 1) as usize;
+            if visited[variable_index] {
+                continue;
+            }
+            visited[variable_index] = true;
+            if let Some(reason) = self.reasons[variable_index].or(formula.variables[variable_index].reason) {
+                reasons.push((position[variable_index], reason));
+                stack.extend(&formula.clauses[reason].literals);
+            }
+        }
+        reasons.sort_unstable();
+        reasons.into_iter().map(|(_, reason)| Clause::id(reason)).collect()
+    }
+}
+
+/// Failed literal probing on the root level.
+///
+/// Both literals of every free variable are assigned on a temporary level and propagated. If a
+/// literal leads to a conflict, its negation is added as unit clause. A literal that is implied by
+/// both literals of a variable is added as unit clause as well, it is derived from the two binary
+/// clauses of the probes, which are removed afterwards. The units are propagated on the root level
+/// right away, so the following probes see them. With `hyper_binary` every propagation of a
+/// probe adds its hyper-binary resolvents, see [`add_hyper_binary_resolvents`].
+/// Each call continues with the variable after the last probed one and stops after `probe_time`.
+pub(crate) fn probe(formula: &mut Formula, timeout: Arc<AtomicBool>) -> Option<FormulaResultType> {
+    let deadline = Instant::now() + formula.preprocess.probe_time;
+    let num_variables = formula.variables.len();
+    let mut prober = Prober::new(num_variables);
+    let (mut failed, mut implied) = (0, 0);
+    let resolvents = formula.statistics.hyper_binary_resolvents;
+    for step in 0..num_variables {
+        if Instant::now() >= deadline || timeout.load(Ordering::SeqCst) {
+            break;
+        }
+        let variable_index = (formula.probe_position + step) % num_variables;
+        let variable = &formula.variables[variable_index];
+        if variable.value != Value::Null
+            || variable.positive_occurrences.is_empty() && variable.negative_occurrences.is_empty()
+            || formula.is_frozen(variable_index)
+        {
+            continue;
+        }
+        let literal = (variable_index + 1) as i16;
+        if let Some(result) = probe_variable(formula, &mut prober, literal, &mut failed, &mut implied) {
+            return Some(result);
+        }
+        formula.probe_position = (variable_index + 1) % num_variables;
+    }
+    debug!(
+        target: "probe",
+        "found {} failed literals, {} implied literals and {} hyper-binary resolvents",
+        failed,
+        implied,
+        formula.statistics.hyper_binary_resolvents - resolvents
+    );
+    None
+}
+
+fn probe_variable(
+    formula: &mut Formula,
+    prober: &mut Prober,
+    literal: i16,
+    failed: &mut usize,
+    implied: &mut usize,
+) -> Option<FormulaResultType> {
+    let mut positive = Vec::new();
+    for probed in [literal, -literal] {
+        if let Err(conflict) = prober.propagate(formula, probed) {
+            let mut hints = prober.hints(formula, &formula.clauses[conflict].literals);
+            hints.push(Clause::id(conflict));
+            prober.undo();
+            debug!(target: "probe", "failed literal {}", probed);
+            *failed += 1;
+            return add_unit(formula, -probed, hints);
+        }
+        if formula.preprocess.hyper_binary {
+            add_hyper_binary_resolvents(formula, prober);
+        }
+        if probed == literal {
+            positive = prober.trail[1..].to_vec();
+        } else {
+            positive.retain(|lit| prober.value(formula, *lit) == Value::True);
+        }
+        prober.undo();
+    }
+    for unit in positive {
+        // the units before were propagated on the root level, which may have decided this variable
+        if formula.literal_value(literal) != Value::Null {
+            break;
+        }
+        if formula.literal_value(unit) == Value::True {
+            continue;
+        }
+        // the hints for the binary clause of each probe, derived by its propagation
+        let mut probe_hints = Vec::new();
+        for probed in [literal, -literal] {
+            let propagated = prober.propagate(formula, probed);
+            probe_hints.push(prober.hints(formula, &[unit]));
+            prober.undo();
+            // the probe fails since the last units, it is probed again by the next call
+            if propagated.is_err() {
+                return None;
+            }
+        }
+        debug!(target: "probe", "{} is implied by {} and {}", unit, literal, -literal);
+        *implied += 1;
+        let mut binaries = Vec::new();
+        for (probed, hints) in [literal, -literal].into_iter().zip(probe_hints) {
+            binaries.push(formula.clauses.len());
+            formula.proof_add(Clause::id(formula.clauses.len()), &[-probed, unit], &hints);
+            formula.insert_clause(vec![-probed, unit], ClauseType::Learned);
+        }
+        let hints: Vec<usize> = binaries.iter().map(|clause_index| Clause::id(*clause_index)).collect();
+        let result = add_unit(formula, unit, hints);
+        for clause_index in binaries {
+            formula.remove_clause(clause_index);
+        }
+        if result.is_some() {
+            return result;
+        }
+    }
+    None
+}
+
+/// Add the hyper-binary resolvents of the propagation of a probe.
+///
+/// The literals of the probe form a tree: a literal that is implied by a binary clause hangs below
+/// the other literal of the clause. A literal that is implied by a longer clause, whose false
+/// literals come from more than one literal of the tree, hangs below their dominator, the
+/// deepest literal all of them are implied by. The dominator implies the literal over binary
+/// clauses and the longer clause, so this implication is added as binary clause. It saves the
+/// long propagation chain later and lets substitution and transitive reduction see the
+/// implication. The number of resolvents of a round is bounded by `HYPER_BINARY_LIMIT`.
+fn add_hyper_binary_resolvents(formula: &mut Formula, prober: &Prober) {
+    let num_variables = formula.variables.len();
+    // the parent of every literal of the tree with the binary clause between them
+    let mut parent: Vec<Option<(i16, usize)>> = vec![None; num_variables];
+    let mut depth = vec![0; num_variables];
+    let mut added = 0;
+    let dominator = |mut a: i16, mut b: i16, parent: &[Option<(i16, usize)>], depth: &[usize]| {
+        let index = |lit: i16| (lit.unsigned_abs() - 1) as usize;
+        while a != b {
+            if depth[index(a)] >= depth[index(b)] {
+                a = parent[index(a)].unwrap().0;
+            } else {
+                b = parent[index(b)].unwrap().0;
+            }
+        }
+        a
+    };
+    for literal in &prober.trail[1..] {
+        let variable_index = (literal.unsigned_abs() - 1) as usize;
+        let reason = prober.reasons[variable_index].unwrap();
+        // the literals of the tree that falsify the other literals of the reason
+        let antecedents: Vec<i16> = formula.clauses[reason]
+            .literals
+            .iter()
+            .filter(|lit| *lit != literal && formula.literal_value(**lit) == Value::Null)
+            .map(|lit| -lit)
+            .collect();
+        // a literal that is implied without the tree, by a reason with root literals only, hangs below the probe
+        let top = match antecedents.split_first() {
+            Some((first, rest)) => rest.iter().fold(*first, |top, lit| dominator(top, *lit, &parent, &depth)),
+            None => prober.trail[0],
+        };
+        let edge = if antecedents.len() <= 1 {
+            reason
+        } else if let Some(binary) = find_binary(formula, -top, *literal) {
+            binary
+        } else {
+            if added >= HYPER_BINARY_LIMIT {
+                return;
+            }
+            // the paths from the dominator propagate the antecedents, then the reason is falsified
+            let mut edges = Vec::new();
+            let mut visited = HashSet::new();
+            for antecedent in antecedents {
+                let mut lit = antecedent;
+                while lit != top && visited.insert(lit) {
+                    let (above, clause_index) = parent[(lit.unsigned_abs() - 1) as usize].unwrap();
+                    edges.push((depth[(lit.unsigned_abs() - 1) as usize], clause_index));
+                    lit = above;
+                }
+            }
+            edges.sort_unstable();
+            edges.push((usize::MAX, reason));
+            // the literals of the clauses that are false on the root level come first
+            let root: Vec<i16> = edges
+                .iter()
+                .flat_map(|(_, clause_index)| formula.clauses[*clause_index].literals.iter().copied())
+                .filter(|lit| formula.literal_value(*lit) == Value::False)
+                .collect();
+            let mut hints = prober.hints(formula, &root);
+            hints.extend(edges.into_iter().map(|(_, clause_index)| Clause::id(clause_index)));
+            let clause_index = formula.clauses.len();
+            debug!(target: "probe", "hyper-binary resolvent {:?}", [-top, *literal]);
+            formula.proof_add(Clause::id(clause_index), &[-top, *literal], &hints);
+            formula.insert_clause(vec![-top, *literal], ClauseType::Learned);
+            formula.statistics.hyper_binary_resolvents += 1;
+            added += 1;
+            clause_index
+        };
+        parent[variable_index] = Some((top, edge));
+        depth[variable_index] = depth[(top.unsigned_abs() - 1) as usize] + 1;
+    }
+}
+
+/// The index of a binary clause with the two literals.
+fn find_binary(formula: &Formula, a: i16, b: i16) -> Option<usize> {
+    let variable = &formula.variables[(b.unsigned_abs() - 1) as usize];
+    let occurrences = if b > 0 { &variable.positive_occurrences } else { &variable.negative_occurrences };
+    occurrences.iter().copied().find(|clause_index| {
+        let clause = &formula.clauses[*clause_index];
+        !clause.deleted && clause.literals.len() == 2 && clause.literals.contains(&a)
+    })
+}
+
+/// Add a unit clause on the root level and propagate it.
+pub(crate) fn add_unit(formula: &mut Formula, unit: i16, hints: Vec<usize>) -> Option<FormulaResultType> {
+    if let Some(result) = formula.add_clauses(vec![unit], hints) {
+        formula.result = result;
+        return Some(result);
+    }
+    unit_propagation(formula)
+}
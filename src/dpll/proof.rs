@@ -0,0 +1,234 @@
+use crate::dpll::schemas::{Clause, Formula};
+use clap::ValueEnum;
+use log::error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum ProofFormat {
+    Text,
+    Binary,
+    Lrat,
+    LratBinary,
+}
+
+/// A DRAT or LRAT proof that is written while the solver runs.
+///
+/// Every learned clause is written as lemma and every removed clause as deletion, an
+/// unsatisfiable formula ends the proof with the empty clause.
+/// LRAT lemmas carry their clause id and the ids of the clauses they are derived from.
+#[derive(Debug)]
+pub struct Proof {
+    writer: BufWriter<File>,
+    format: ProofFormat,
+    // LRAT deletions are written with the id of the last added clause
+    last_id: usize,
+}
+
+impl Proof {
+    pub fn create(path: &PathBuf, format: ProofFormat) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            format,
+            last_id: 0,
+        })
+    }
+
+    /// LRAT proofs need the ids of the antecedents of every learned clause.
+    pub fn with_hints(&self) -> bool {
+        matches!(self.format, ProofFormat::Lrat | ProofFormat::LratBinary)
+    }
+
+    /// Binary proofs write every number as variable length integer with 7 bit per byte, the
+    /// highest bit marks that another byte follows.
+    fn write_binary_number(&mut self, mut number: usize) -> std::io::Result<()> {
+        while number > 127 {
+            self.writer.write_all(&[(number & 127) as u8 | 128])?;
+            number >>= 7;
+        }
+        self.writer.write_all(&[number as u8])
+    }
+
+    /// Every literal is mapped to 2 * variable + sign in binary proofs.
+    fn write_binary_literal(&mut self, literal: i16) -> std::io::Result<()> {
+        self.write_binary_number(2 * literal.unsigned_abs() as usize + (literal < 0) as usize)
+    }
+
+    /// Negative hints are the candidate clauses of a RAT lemma.
+    fn write_lemma(&mut self, id: usize, literals: &[i16], hints: &[i64]) -> std::io::Result<()> {
+        self.last_id = id;
+        match self.format {
+            ProofFormat::Text => {
+                for lit in literals {
+                    write!(self.writer, "{} ", lit)?;
+                }
+                self.writer.write_all(b"0\n")
+            }
+            ProofFormat::Binary => {
+                self.writer.write_all(b"a")?;
+                for lit in literals {
+                    self.write_binary_literal(*lit)?;
+                }
+                self.writer.write_all(&[0])
+            }
+            ProofFormat::Lrat => {
+                write!(self.writer, "{} ", id)?;
+                for lit in literals {
+                    write!(self.writer, "{} ", lit)?;
+                }
+                self.writer.write_all(b"0 ")?;
+                for hint in hints {
+                    write!(self.writer, "{} ", hint)?;
+                }
+                self.writer.write_all(b"0\n")
+            }
+            ProofFormat::LratBinary => {
+                self.writer.write_all(b"a")?;
+                self.write_binary_number(2 * id)?;
+                for lit in literals {
+                    self.write_binary_literal(*lit)?;
+                }
+                self.writer.write_all(&[0])?;
+                for hint in hints {
+                    self.write_binary_number(2 * hint.unsigned_abs() as usize + (*hint < 0) as usize)?;
+                }
+                self.writer.write_all(&[0])
+            }
+        }
+    }
+
+    fn write_deletion(&mut self, id: usize, literals: &[i16]) -> std::io::Result<()> {
+        match self.format {
+            ProofFormat::Text => {
+                self.writer.write_all(b"d ")?;
+                for lit in literals {
+                    write!(self.writer, "{} ", lit)?;
+                }
+                self.writer.write_all(b"0\n")
+            }
+            ProofFormat::Binary => {
+                self.writer.write_all(b"d")?;
+                for lit in literals {
+                    self.write_binary_literal(*lit)?;
+                }
+                self.writer.write_all(&[0])
+            }
+            ProofFormat::Lrat => writeln!(self.writer, "{} d {} 0", self.last_id, id),
+            ProofFormat::LratBinary => {
+                self.writer.write_all(b"d")?;
+                self.write_binary_number(2 * id)?;
+                self.writer.write_all(&[0])
+            }
+        }
+    }
+
+    pub fn add(&mut self, id: usize, literals: &[i16], hints: &[usize]) {
+        let hints: Vec<i64> = hints.iter().map(|hint| *hint as i64).collect();
+        if let Err(e) = self.write_lemma(id, literals, &hints) {
+            error!(target: "proof", "could not write lemma {:?}: {}", literals, e);
+        }
+    }
+
+    /// Add a RAT lemma on its first literal. Every candidate clause with the negated literal comes
+    /// with the hints that derive a conflict from its resolvent with the lemma.
+    pub fn add_rat(&mut self, id: usize, literals: &[i16], candidates: &[(usize, Vec<usize>)]) {
+        let hints: Vec<i64> = candidates
+            .iter()
+            .flat_map(|(candidate, hints)| std::iter::once(-(*candidate as i64)).chain(hints.iter().map(|hint| *hint as i64)))
+            .collect();
+        if let Err(e) = self.write_lemma(id, literals, &hints) {
+            error!(target: "proof", "could not write lemma {:?}: {}", literals, e);
+        }
+    }
+
+    pub fn delete(&mut self, id: usize, literals: &[i16]) {
+        if let Err(e) = self.write_deletion(id, literals) {
+            error!(target: "proof", "could not write deletion {:?}: {}", literals, e);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            error!(target: "proof", "could not flush proof: {}", e);
+        }
+    }
+}
+
+impl Formula {
+    /// Log every following change of the clause database to the given proof.
+    pub fn set_proof(&mut self, proof: Proof) {
+        self.proof = Some(proof);
+    }
+
+    /// Stop logging and return the proof, it is flushed before.
+    pub fn take_proof(&mut self) -> Option<Proof> {
+        let mut proof = self.proof.take();
+        if let Some(proof) = proof.as_mut() {
+            proof.flush();
+        }
+        proof
+    }
+
+    /// The antecedents of learned clauses are needed for LRAT proofs and unsatisfiable cores.
+    pub(crate) fn proof_with_hints(&self) -> bool {
+        self.proof.as_ref().is_some_and(|proof| proof.with_hints()) || self.resolution_trail.is_some()
+    }
+
+    /// Add a derived clause to the proof, the empty clause closes the proof of unsatisfiability.
+    pub(crate) fn proof_add(&mut self, id: usize, literals: &[i16], hints: &[usize]) {
+        self.record_antecedents(id, literals, hints);
+        if let Some(proof) = self.proof.as_mut() {
+            proof.add(id, literals, hints);
+        }
+    }
+
+    /// Add a clause that is only RAT on its first literal to the proof, see [`Proof::add_rat`].
+    pub(crate) fn proof_add_rat(&mut self, id: usize, literals: &[i16], candidates: &[(usize, Vec<usize>)]) {
+        let antecedents: Vec<usize> = candidates.iter().flat_map(|(candidate, hints)| std::iter::once(*candidate).chain(hints.iter().copied())).collect();
+        self.record_antecedents(id, literals, &antecedents);
+        if let Some(proof) = self.proof.as_mut() {
+            proof.add_rat(id, literals, candidates);
+        }
+    }
+
+    pub(crate) fn proof_delete(&mut self, id: usize, literals: &[i16]) {
+        if let Some(proof) = self.proof.as_mut() {
+            proof.delete(id, literals);
+        }
+    }
+
+    /// The ids of all clauses that are needed to falsify the given clause by unit propagation.
+    /// Starting at the false literals of the clause we follow the reasons of the implied variables
+    /// back to the decisions. The reasons are ordered like the assigment stack, so every reason is
+    /// a unit once the ones before are propagated, the given clause comes last.
+    pub(crate) fn resolution_hints(&self, clause_index: usize) -> Vec<usize> {
+        let mut position = vec![0; self.variables.len()];
+        for (index, assignment) in self.assigment_stack.iter().enumerate() {
+            position[assignment.variable_index] = index;
+        }
+        let mut visited = vec![false; self.variables.len()];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut reasons: Vec<(usize, usize)> = Vec::new();
+        let push_literals = |literals: &[i16], stack: &mut Vec<usize>| {
+            stack.extend(literals.iter().map(|lit| (lit.unsigned_abs() - 1) as usize));
+        };
+        push_literals(&self.clauses[clause_index].literals, &mut stack);
+        while let Some(variable_index) = stack.pop() {
+            if visited[variable_index] {
+                continue;
+            }
+            visited[variable_index] = true;
+            if let Some(reason) = self.variables[variable_index].reason {
+                reasons.push((position[variable_index], reason));
+                push_literals(&self.clauses[reason].literals, &mut stack);
+            }
+        }
+        reasons.sort_unstable();
+        reasons
+            .into_iter()
+            .map(|(_, reason)| Clause::id(reason))
+            .chain(std::iter::once(Clause::id(clause_index)))
+            .collect()
+    }
+}
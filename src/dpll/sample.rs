@@ -0,0 +1,125 @@
+use crate::dpll::approx::{cell_models, random_hash, ApproxCounter, Xor};
+use log::{debug, info, warn};
+use num_traits::ToPrimitive;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// the cell sizes of UniGen with a tolerance of 16: pivot = 3 e^(1/2) (1 + 1/16)^2 and kappa = 0.638
+const PIVOT: f64 = 6.0;
+const LOW_THRESHOLD: usize = 4;
+const HIGH_THRESHOLD: usize = 10;
+// the number of tries with different hashes before a sample is given up
+const ROUNDS: usize = 20;
+
+/// Near uniform sampler of models in the style of UniGen.
+///
+/// The number of models is estimated by the [`ApproxCounter`], which gives the number of random
+/// XORs that split the models into cells of about `PIVOT` models. A sample is drawn from a random
+/// cell whose size is between the thresholds, so every model has about the same probability.
+/// The models of a cell are sorted before one is picked, so the samples only depend on the seed
+/// and not on the order the solver finds the models in. Formulas with only a few models are
+/// enumerated once and sampled directly.
+#[derive(Debug)]
+pub struct Sampler {
+    clauses: Vec<Vec<i16>>,
+    num_variables: usize,
+    // the variables of the samples, starting with 1
+    sampling_set: Vec<i16>,
+    rng: StdRng,
+    // all models, if there are not more than the high threshold
+    models: Option<Vec<Vec<i16>>>,
+    // the largest number of XORs that is tried for a cell
+    xors: usize,
+    prepared: bool,
+    pub cell_queries: usize,
+}
+
+impl Sampler {
+    /// Sample models projected onto the given variables, without projection all variables of the
+    /// formula are used.
+    pub fn new(num_variables: usize, clauses: Vec<Vec<i16>>, projection: Option<Vec<i16>>, seed: u64) -> Self {
+        let sampling_set = projection.unwrap_or_else(|| (1..=num_variables as i16).collect());
+        Self {
+            clauses,
+            num_variables,
+            sampling_set,
+            rng: StdRng::seed_from_u64(seed),
+            models: None,
+            xors: 0,
+            prepared: false,
+            cell_queries: 0,
+        }
+    }
+
+    /// Whether the formula has no models, known only after the first sample was requested. A
+    /// sampler that gives up on finding a cell is not unsatisfiable.
+    pub fn is_unsatisfiable(&self) -> bool {
+        self.models.as_ref().is_some_and(|models| models.is_empty())
+    }
+
+    /// Enumerate the formula if it has only a few models, otherwise estimate their number and
+    /// choose the number of XORs for the cells.
+    fn prepare(&mut self) {
+        self.prepared = true;
+        let mut models = self.cell(&[], HIGH_THRESHOLD + 1);
+        if models.len() <= HIGH_THRESHOLD {
+            models.sort();
+            info!(target: "sample", "sampling from all {} models", models.len());
+            self.models = Some(models);
+            return;
+        }
+        let count = ApproxCounter::new(
+            self.num_variables,
+            self.clauses.clone(),
+            Some(self.sampling_set.clone()),
+            0.8,
+            0.2,
+            self.rng.gen(),
+        )
+        .expect("The tolerance and the confidence of the count are valid")
+        .count();
+        // the count can be too large for a float, so only its highest bits are converted
+        let shift = count.bits().saturating_sub(52);
+        let log_count = (count >> shift).to_f64().unwrap().log2() + shift as f64;
+        self.xors = (log_count + 1.8f64.log2() - PIVOT.log2()).ceil().max(0.0) as usize;
+        info!(target: "sample", "about 2^{:.1} models, cells with up to {} xors", log_count, self.xors);
+    }
+
+    fn cell(&mut self, xors: &[Xor], limit: usize) -> Vec<Vec<i16>> {
+        self.cell_queries += 1;
+        cell_models(self.num_variables, &self.clauses, &self.sampling_set, xors, limit)
+    }
+}
+
+impl Iterator for Sampler {
+    type Item = Vec<i16>;
+
+    /// The next sample, None if the formula is unsatisfiable or no cell of the right size was found.
+    fn next(&mut self) -> Option<Vec<i16>> {
+        if !self.prepared {
+            self.prepare();
+        }
+        if let Some(models) = &self.models {
+            if models.is_empty() {
+                return None;
+            }
+            let index = self.rng.gen_range(0..models.len());
+            return Some(models[index].clone());
+        }
+        for round in 0..ROUNDS {
+            // the estimate may be off, so a few smaller numbers of XORs are tried as well
+            for xors in self.xors.saturating_sub(3)..=self.xors {
+                let hash = random_hash(&mut self.rng, &self.sampling_set, xors);
+                let mut models = self.cell(&hash, HIGH_THRESHOLD + 1);
+                debug!(target: "sample", "round {}: cell with {} xors has {} models", round, xors, models.len());
+                if (LOW_THRESHOLD..=HIGH_THRESHOLD).contains(&models.len()) {
+                    models.sort();
+                    let index = self.rng.gen_range(0..models.len());
+                    return Some(models.swap_remove(index));
+                }
+            }
+        }
+        warn!(target: "sample", "found no cell with {} to {} models", LOW_THRESHOLD, HIGH_THRESHOLD);
+        None
+    }
+}
@@ -1,3 +1,9 @@
+use crate::dpll::dpll::backtrack;
+use crate::dpll::local_search::LocalSearchPhases;
+use crate::dpll::preprocess::PreprocessConfig;
+use crate::dpll::proof::Proof;
+use crate::dpll::unsat_core::ResolutionTrail;
+use crate::dpll::xor::{GaussMatrix, XorConstraint};
 use crate::dpll::schemas::Value::Null;
 use clap::ValueEnum;
 use log::{debug, error, warn};
@@ -10,6 +16,7 @@ pub enum Value {
     False,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum HeuristicType {
     None,
@@ -20,6 +27,12 @@ pub enum HeuristicType {
     VSIDS,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum SolveMode {
+    Cdcl,
+    Local,
+}
+
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum AssigmentType {
     Forced,
@@ -30,7 +43,9 @@ pub enum AssigmentType {
 pub enum SetResultType {
     Conflict {
         depth: usize,
-        clause: Vec<i16>
+        clause: Vec<i16>,
+        // ids of the clauses the learned clause is derived from, only collected for LRAT proofs and cores
+        hints: Vec<usize>,
     },
     Success,
 }
@@ -43,10 +58,13 @@ pub enum FormulaResultType {
     Timeout,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum ClauseType {
     Original,
     Learned,
+    /// Derived by the preprocessing from other clauses, like the resolvents of the variable
+    /// elimination. These clauses are kept like original ones but are not part of the input.
+    Derived,
 }
 
 /// The clause struct
@@ -61,19 +79,31 @@ pub struct Clause {
     pub(crate) watched: (usize, usize),
     pub clause_type: ClauseType,
     pub activity: u16,
+    // deleted clauses keep their index but are removed from all occurrences
+    pub deleted: bool,
 }
 
 impl Clause {
+    /// The id of a clause is its index starting with 1, like the clause numbers of the DIMACS
+    /// file. Deleted clauses keep their index, so the id of a clause never changes.
+    pub fn id(clause_index: usize) -> usize {
+        clause_index + 1
+    }
+
+    pub fn literals(&self) -> &[i16] {
+        &self.literals
+    }
+
     pub fn find_new_variable_to_watch(
         &mut self,
         variable_index: usize,
-        variables: &mut Vec<Variable>,
+        variables: &mut [Variable],
         clause_index: usize,
     ) -> Result<Option<(usize, Value, usize)>, i8> {
         let my_watched_index;
         let other_watched_index;
         debug!(target: "find_new_variable_to_watch", "watched: {:?}", self.watched);
-        if variable_index == (self.literals[self.watched.0].abs() - 1) as usize {
+        if variable_index == (self.literals[self.watched.0].unsigned_abs() - 1) as usize {
             my_watched_index = self.watched.0;
             other_watched_index = self.watched.1;
         } else {
@@ -86,7 +116,7 @@ impl Clause {
         debug!(target: "find_new_variable_to_watch", "num of literals: {}", self.literals.len());
         for literal_index in 0..self.literals.len() {
             let lit = self.literals[literal_index];
-            let variable = &mut variables[lit.abs() as usize - 1];
+            let variable = &mut variables[lit.unsigned_abs() as usize - 1];
             debug!(target: "find_new_variable_to_watch", "current literal_index: {}", literal_index);
             debug!(target: "find_new_variable_to_watch", "current lit: {}", lit);
             //debug!(target: "find_new_variable_to_watch", "current variable: {:?}", variable);
@@ -119,11 +149,11 @@ impl Clause {
 
             // remove the clause from the old variable that is not watched anymore !
             if old_lit_pos {
-                variables[old_lit.abs() as usize - 1]
+                variables[old_lit.unsigned_abs() as usize - 1]
                     .watched_pos_occurrences
                     .remove(&clause_index);
             } else {
-                variables[old_lit.abs() as usize - 1]
+                variables[old_lit.unsigned_abs() as usize - 1]
                     .watched_neg_occurrences
                     .remove(&clause_index);
             }
@@ -139,17 +169,17 @@ impl Clause {
             } else {
                 Value::False
             };
-            let variables_index = self.literals[other_watched_index].abs() as usize - 1;
+            let variables_index = self.literals[other_watched_index].unsigned_abs() as usize - 1;
             debug!(target: "find_new_variable_to_watch", "new unit clause found!: {:?}, variable: {}", variables[variables_index], variables_index+1);
             return Ok(Some((
-                self.literals[other_watched_index].abs() as usize - 1,
+                self.literals[other_watched_index].unsigned_abs() as usize - 1,
                 value,
                 clause_index,
             )));
         }
         // conflict
         warn!(target: "find_new_variable_to_watch", "new conflict no free variable to watch!: {}", clause_index);
-        return Err(0);
+        Err(0)
     }
 }
 
@@ -169,6 +199,8 @@ pub struct Variable {
     pub depth: usize,
     // None for branching and clauses index as usize for unit propagation trigger.
     pub reason: Option<usize>,
+    // the polarity of the decisions, set by the local search runs inside CDCL
+    pub phase: bool,
 }
 
 #[derive(Debug)]
@@ -179,9 +211,9 @@ pub enum PureType {
 
 impl Variable {
     pub(crate) fn is_pure(&self) -> Option<PureType> {
-        if self.positive_occurrences.len() == 0 {
+        if self.positive_occurrences.is_empty() {
             Some(PureType::Negative)
-        } else if self.negative_occurrences.len() == 0 {
+        } else if self.negative_occurrences.is_empty() {
             Some(PureType::Positive)
         } else {
             None
@@ -196,10 +228,18 @@ impl Variable {
 /// This struct is used to store the assignments in the [`assigment_stack`](Vec<Assignment>).
 #[derive(Copy, Clone, Debug)]
 pub struct Assignment {
-    pub(crate) variable_index: usize,
-    pub(crate) assigment_type: AssigmentType,
-    pub(crate) value: Value,
-    pub(crate) depth: usize,
+    pub variable_index: usize,
+    pub assigment_type: AssigmentType,
+    pub value: Value,
+    pub depth: usize,
+}
+
+/// The counts of the simplifications over all solve calls.
+#[derive(Debug, Default, Clone)]
+pub struct Statistics {
+    pub hyper_binary_resolvents: usize,
+    pub transitive_reductions: usize,
+    pub gate_eliminations: usize,
 }
 
 /// The formula struct
@@ -217,6 +257,43 @@ pub struct Formula {
     pub heuristic_type: HeuristicType,
     pub original_clause_vector_length: usize,
     pub depth: usize,
+    // activation literals of the open scopes, they are decided first in every solve call
+    pub(crate) scopes: Vec<i16>,
+    // literals that are assumed to be true in the next solve calls
+    pub(crate) assumptions: Vec<i16>,
+    // the activation literals responsible for the last unsatisfiable result, empty if the formula
+    // is unsatisfiable without any assumption
+    pub(crate) failed_assumptions: Vec<i16>,
+    // the variable index of every variable of the caller, the activation literals and the variables
+    // added by the solver are not part of it
+    pub(crate) user_variables: Vec<usize>,
+    // DRAT proof of the learned and deleted clauses, if one is requested
+    pub(crate) proof: Option<Proof>,
+    // antecedents of the learned clauses, if an unsatisfiable core is requested
+    pub(crate) resolution_trail: Option<ResolutionTrail>,
+    // native XOR constraints, they are propagated by Gauss-Jordan elimination
+    pub(crate) xor_constraints: Vec<XorConstraint>,
+    // the XOR constraints split into independent matrices, built again after a constraint is added
+    pub(crate) gauss_matrices: Option<Vec<GaussMatrix>>,
+    // the number of conflicts over all solve calls
+    pub(crate) conflicts: usize,
+    // short local search runs between the conflicts that set the saved phases, if enabled
+    pub local_search_phases: Option<LocalSearchPhases>,
+    // the simplifications of the first solve call
+    pub preprocess: PreprocessConfig,
+    pub(crate) preprocessed: bool,
+    // the removed clauses with their witness literal, to extend the model to the removed variables
+    pub(crate) reconstruction: Vec<(i16, Vec<i16>)>,
+    // the variable where the next probing round starts
+    pub(crate) probe_position: usize,
+    // the number of assignments over all solve calls, the effort of the vivification depends on it
+    pub(crate) assignments: usize,
+    pub(crate) last_vivification: usize,
+    // the clauses that were vivified already
+    pub(crate) vivified: HashSet<usize>,
+    // the binary clause where the next transitive reduction starts
+    pub(crate) transitive_position: usize,
+    pub(crate) statistics: Statistics,
 }
 
 impl Formula {
@@ -229,6 +306,7 @@ impl Formula {
             panic!("Null value");
         }
         self.assigment_stack.push(assignment);
+        self.assignments += 1;
     }
 
     pub fn unit_queue_push(&mut self, unit: (usize, Value, usize)) {
@@ -241,71 +319,124 @@ impl Formula {
 
     /// Add a new learned clause to the formular by a list of literates,
     /// all dependent variables get updated accordingly.
-    pub fn add_clauses(&mut self, literals: Vec<i16>) -> Option<FormulaResultType> {
+    /// The hints are the ids of the clauses the learned clause is derived from, see [`Clause::id`].
+    pub fn add_clauses(&mut self, literals: Vec<i16>, hints: Vec<usize>) -> Option<FormulaResultType> {
+        self.proof_add(Clause::id(self.clauses.len()), &literals, &hints);
+        self.insert_clause(literals, ClauseType::Learned)
+    }
+
+    /// The value of a literal under the current assignment, a negative literal is true if its
+    /// variable is false.
+    pub fn literal_value(&self, literal: i16) -> Value {
+        match (self.variables[(literal.unsigned_abs() - 1) as usize].value, literal > 0) {
+            (Null, _) => Null,
+            (Value::True, true) | (Value::False, false) => Value::True,
+            _ => Value::False,
+        }
+    }
+
+    /// Insert a clause and pick its watched literals under the current assignment.
+    /// Free and satisfied literals are watched first. If only one of them is left, the clause is a unit,
+    /// and the second watch goes to the false literal with the highest depth so that both watches
+    /// are valid again after backtracking. A clause that is false is only unsatisfiable on depth 0,
+    /// above it the assignments down to its deepest literal are undone first.
+    pub(crate) fn insert_clause(
+        &mut self,
+        literals: Vec<i16>,
+        clause_type: ClauseType,
+    ) -> Option<FormulaResultType> {
+        // a clause that is false under the current assignment only contradicts the formula if all its
+        // literals are false on depth 0, otherwise the search goes back below its deepest literal
+        if literals.iter().all(|lit| self.literal_value(*lit) == Value::False) {
+            let deepest = literals.iter().map(|lit| self.variables[(lit.unsigned_abs() - 1) as usize].depth).max();
+            if let Some(depth) = deepest.filter(|depth| *depth > 0) {
+                debug!(target: "insert_clause", "clause {:?} is false, backtracking to depth {}", &literals, depth - 1);
+                backtrack(self, depth - 1);
+                self.units.clear();
+            }
+        }
         let clause_index = self.clauses.len();
         // UPDATE all variables that appear in the new clause
-        // Do wee need to update all variables or only the watched ones ??
         for lit in &literals {
-            let variables_index = (lit.abs() - 1) as usize;
+            let variables_index = (lit.unsigned_abs() - 1) as usize;
             if *lit > 0 {
                 self.variables[variables_index]
                     .positive_occurrences
                     .push(clause_index);
-                if *lit == literals[0] || *lit == literals[1] {
-                    self.variables[variables_index]
-                        .watched_pos_occurrences
-                        .insert(clause_index);
-                }
             } else {
                 self.variables[variables_index]
                     .negative_occurrences
                     .push(clause_index);
-                if *lit == literals[0] || *lit == literals[1] {
-                    self.variables[variables_index]
-                        .watched_neg_occurrences
-                        .insert(clause_index);
-                }
             }
         }
-        let mut watched = (0, 1);
-        // TODO remove for release only for testing
         let mut free_watched: Vec<usize> = Vec::new();
+        let mut deepest_false: Option<usize> = None;
         for (index, lit) in literals.iter().enumerate() {
-            let variables_index = (lit.abs() - 1) as usize;
-            if self.variables[variables_index].value != Null {
-                debug!(target: "add_clauses", "literal: {}={:?}", lit,&self.variables[variables_index].value);
+            if self.literal_value(*lit) != Value::False {
+                free_watched.push(index);
                 continue;
             }
-            free_watched.push(index);
-        }
-        if free_watched.len() == 0 {
-            return Some(FormulaResultType::Unsatisfiable)
+            debug!(target: "insert_clause", "literal: {} is false", lit);
+            let depth = self.variables[(lit.unsigned_abs() - 1) as usize].depth;
+            if deepest_false.is_none_or(|i| {
+                depth > self.variables[(literals[i].unsigned_abs() - 1) as usize].depth
+            }) {
+                deepest_false = Some(index);
+            }
         }
-        if free_watched.len() < 2 {
-            debug!(target: "add_clauses", "new added clauses is a unit clauses !!!");
-            watched = (free_watched[0], free_watched[0]);
-
-            let lit = literals[free_watched[0]];
-            let value = if lit > 0 { Value::True } else { Value::False };
-            self.units.clear();
-            self.units.push_back(((lit.abs() - 1) as usize, value, clause_index));
-        } else {
-            watched = (free_watched[0], free_watched[1]);
+
+        let mut result = None;
+        let watched = match free_watched.len() {
+            0 => {
+                result = Some(FormulaResultType::Unsatisfiable);
+                (0, literals.len().min(2).saturating_sub(1))
+            }
+            1 => {
+                let lit = literals[free_watched[0]];
+                if self.literal_value(lit) == Null {
+                    debug!(target: "insert_clause", "new added clauses is a unit clauses !!!");
+                    let value = if lit > 0 { Value::True } else { Value::False };
+                    if clause_type == ClauseType::Learned {
+                        self.units.clear();
+                    }
+                    self.units
+                        .push_back(((lit.unsigned_abs() - 1) as usize, value, clause_index));
+                }
+                (free_watched[0], deepest_false.unwrap_or(free_watched[0]))
+            }
+            _ => (free_watched[0], free_watched[1]),
+        };
+        if !literals.is_empty() {
+            self.watch_literal(literals[watched.0], clause_index);
+            self.watch_literal(literals[watched.1], clause_index);
         }
 
-        let clause = Clause {
+        self.clauses.push(Clause {
             literals,
             watched,
-            clause_type: ClauseType::Learned,
+            clause_type,
             activity: 0,
-        };
-        self.clauses.push(clause);
-        return None
+            deleted: false,
+        });
+        // the clause is false on depth 0, the empty clause follows from it and the reasons of its literals
+        if result.is_some() && !self.clauses[clause_index].literals.is_empty() {
+            let hints = self.resolution_hints(clause_index);
+            self.proof_add(Clause::id(clause_index + 1), &[], &hints);
+        }
+        result
+    }
+
+    fn watch_literal(&mut self, literal: i16, clause_index: usize) {
+        let variable = &mut self.variables[(literal.unsigned_abs() - 1) as usize];
+        if literal > 0 {
+            variable.watched_pos_occurrences.insert(clause_index);
+        } else {
+            variable.watched_neg_occurrences.insert(clause_index);
+        }
     }
+
     /// Removes a learned clause from the formular by the clause index it panics if the index auf the
     /// clauses points to an original clauses!
-    /// Also, the operation of deleting the clause index from the positive and negativ occurrences
-    /// are really expressive, we first have to find the index of the value to remove it ...
     pub fn delete_clauses(&mut self, clause_index: usize) {
         if self.clauses[clause_index].clause_type != ClauseType::Learned {
             panic!(
@@ -314,48 +445,57 @@ impl Formula {
                 clause_index, &self.clauses[clause_index]
             )
         }
-        let clause = self.clauses.remove(clause_index);
+        self.remove_clause(clause_index);
+    }
 
-        for lit in &clause.literals {
-            let variables_index = (lit.abs() - 1) as usize;
-            if *lit > 0 {
-                // TODO there must be a better way, this is to fucking expensive !!
-                let index = self.variables[variables_index]
-                    .positive_occurrences
-                    .iter()
-                    .position(|&x| x == clause_index)
-                    .unwrap();
-                self.variables[variables_index]
-                    .positive_occurrences
-                    .remove(index);
-                // maybe the hashset lookup ist less expensive than the if statement ?
-                if *lit == clause.literals[clause.watched.0]
-                    || *lit == clause.literals[clause.watched.1]
-                {
-                    self.variables[variables_index]
-                        .watched_pos_occurrences
-                        .remove(&clause_index);
-                }
+    /// Removes any clause from the formula. The clause stays in the clause vector as deleted so
+    /// that the indexes of all other clauses stay valid.
+    /// Also, the operation of deleting the clause index from the positive and negativ occurrences
+    /// are really expressive, we first have to find the index of the value to remove it ...
+    pub(crate) fn remove_clause(&mut self, clause_index: usize) {
+        if self.clauses[clause_index].deleted {
+            return;
+        }
+        self.clauses[clause_index].deleted = true;
+        let literals = self.clauses[clause_index].literals.clone();
+        self.proof_delete(Clause::id(clause_index), &literals);
+        for lit in literals {
+            let variable = &mut self.variables[(lit.unsigned_abs() - 1) as usize];
+            let (occurrences, watched) = if lit > 0 {
+                (
+                    &mut variable.positive_occurrences,
+                    &mut variable.watched_pos_occurrences,
+                )
             } else {
-                let index = self.variables[variables_index]
-                    .negative_occurrences
-                    .iter()
-                    .position(|&x| x == clause_index)
-                    .unwrap();
-                self.variables[variables_index]
-                    .negative_occurrences
-                    .remove(index);
-                if *lit == clause.literals[clause.watched.0]
-                    || *lit == clause.literals[clause.watched.1]
-                {
-                    self.variables[variables_index]
-                        .watched_neg_occurrences
-                        .remove(&clause_index);
-                }
+                (
+                    &mut variable.negative_occurrences,
+                    &mut variable.watched_neg_occurrences,
+                )
+            };
+            if let Some(index) = occurrences.iter().position(|&x| x == clause_index) {
+                occurrences.remove(index);
             }
+            watched.remove(&clause_index);
         }
     }
+
+    /// A clause is locked as long as it is the reason of an assigned variable.
+    pub(crate) fn is_reason(&self, clause_index: usize) -> bool {
+        self.clauses[clause_index].literals.iter().any(|lit| {
+            self.variables[(lit.unsigned_abs() - 1) as usize].reason == Some(clause_index)
+        })
+    }
+
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
     pub fn assigment_stack_is_empty(&self) -> bool {
-        return self.assigment_stack.is_empty();
+        self.assigment_stack.is_empty()
     }
 }
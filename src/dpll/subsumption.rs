@@ -0,0 +1,123 @@
+use crate::dpll::schemas::{Clause, ClauseType, Formula, Value};
+use log::debug;
+use std::cmp::Reverse;
+
+// the number of candidate clauses that are checked in one round during the search
+const INPROCESS_EFFORT: usize = 1_000_000;
+
+/// A bit for every literal, a clause can only be a subset of another if all its bits are set in
+/// the signature of the other one.
+fn signature(literals: &[i16]) -> u64 {
+    literals
+        .iter()
+        .fold(0, |signature, lit| signature | 1 << ((2 * lit.unsigned_abs() as u64 + u64::from(*lit < 0)) % 64))
+}
+
+impl Formula {
+    fn occurrences(&self, literal: i16) -> &[usize] {
+        let variable = &self.variables[(literal.unsigned_abs() - 1) as usize];
+        if literal > 0 {
+            &variable.positive_occurrences
+        } else {
+            &variable.negative_occurrences
+        }
+    }
+
+    /// Backward subsumption and self-subsuming resolution with the clauses of the queue.
+    ///
+    /// For every clause C of the queue, shortest first, the clauses with its rarest literal or
+    /// the negation of it are candidates. A candidate that contains C is removed. If a candidate
+    /// contains C with one literal negated, it is strengthened by removing this literal, the
+    /// strengthened clause is added to the queue again. The signatures of the clauses reject
+    /// most candidates without comparing their literals.
+    /// With `learned_only` only learned clauses that are not the reason of an assignment are
+    /// changed, and a clause is only strengthened if it keeps two literals that are not false, so
+    /// this can be done during the search. The effort is the number of candidates to check.
+    /// Returns the number of subsumed and strengthened clauses.
+    pub(crate) fn subsume(&mut self, mut queue: Vec<usize>, learned_only: bool, mut effort: usize) -> (usize, usize) {
+        let mut signatures: Vec<u64> = self.clauses.iter().map(|clause| signature(&clause.literals)).collect();
+        queue.sort_by_key(|clause_index| Reverse(self.clauses[*clause_index].literals.len()));
+        let (mut subsumed, mut strengthened) = (0, 0);
+        while let Some(clause_index) = queue.pop() {
+            if self.clauses[clause_index].deleted || self.clauses[clause_index].literals.is_empty() {
+                continue;
+            }
+            let literals = self.clauses[clause_index].literals.clone();
+            // a tautology would strengthen the clauses with its negated literals
+            if literals.iter().any(|lit| literals.contains(&-lit)) {
+                continue;
+            }
+            let pivot = *literals
+                .iter()
+                .min_by_key(|lit| self.occurrences(**lit).len() + self.occurrences(-**lit).len())
+                .unwrap();
+            let candidates: Vec<usize> = self.occurrences(pivot).iter().chain(self.occurrences(-pivot)).copied().collect();
+            for candidate in candidates {
+                let other = &self.clauses[candidate];
+                if candidate == clause_index || other.deleted || other.literals.len() < literals.len() {
+                    continue;
+                }
+                if learned_only && (other.clause_type != ClauseType::Learned || self.is_reason(candidate)) {
+                    continue;
+                }
+                if effort == 0 {
+                    return (subsumed, strengthened);
+                }
+                effort -= 1;
+                // only one literal may be missing, it has to be there negated
+                if (signatures[clause_index] & !signatures[candidate]).count_ones() > 1 {
+                    continue;
+                }
+                let mut missing = literals.iter().filter(|lit| !other.literals.contains(lit));
+                match (missing.next(), missing.next()) {
+                    (None, _) => {
+                        debug!(target: "subsume", "{:?} subsumes {:?}", &literals, &other.literals);
+                        // an original clause can only be removed if the subsuming clause stays
+                        if other.clause_type != ClauseType::Learned && self.clauses[clause_index].clause_type == ClauseType::Learned {
+                            self.clauses[clause_index].clause_type = ClauseType::Derived;
+                        }
+                        self.remove_clause(candidate);
+                        subsumed += 1;
+                    }
+                    (Some(lit), None) if other.literals.contains(&-lit) => {
+                        let shorter: Vec<i16> = other.literals.iter().copied().filter(|l| *l != -lit).collect();
+                        if learned_only && shorter.iter().filter(|l| self.literal_value(**l) != Value::False).count() < 2 {
+                            continue;
+                        }
+                        debug!(target: "subsume", "{:?} strengthens {:?} to {:?}", &literals, &other.literals, &shorter);
+                        // the strengthened clause is derived from both clauses, so the core can go back to them
+                        let clause_type = match other.clause_type {
+                            ClauseType::Learned => ClauseType::Learned,
+                            _ => ClauseType::Derived,
+                        };
+                        let shorter_index = self.clauses.len();
+                        self.proof_add(Clause::id(shorter_index), &shorter, &[Clause::id(clause_index), Clause::id(candidate)]);
+                        signatures.push(signature(&shorter));
+                        let result = self.insert_clause(shorter, clause_type);
+                        self.remove_clause(candidate);
+                        strengthened += 1;
+                        if let Some(result) = result {
+                            self.result = result;
+                            return (subsumed, strengthened);
+                        }
+                        queue.push(shorter_index);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (subsumed, strengthened)
+    }
+
+    /// Subsumption and self-subsuming resolution on all clauses.
+    pub(crate) fn subsume_all(&mut self) -> (usize, usize) {
+        let queue: Vec<usize> = (0..self.clauses.len()).filter(|clause_index| !self.clauses[*clause_index].deleted).collect();
+        self.subsume(queue, false, usize::MAX)
+    }
+
+    /// Remove and strengthen learned clauses with all clauses during the search.
+    pub(crate) fn subsume_learned(&mut self) -> (usize, usize) {
+        let queue: Vec<usize> = (0..self.clauses.len()).filter(|clause_index| !self.clauses[*clause_index].deleted).collect();
+        self.subsume(queue, true, INPROCESS_EFFORT)
+    }
+}
@@ -0,0 +1,78 @@
+use crate::dpll::equivalence::{node, ImplicationGraph};
+use crate::dpll::schemas::Formula;
+use log::debug;
+
+// the number of edges of the implication graph that are visited at most in one round
+const EFFORT: usize = 1_000_000;
+
+impl ImplicationGraph {
+    /// Whether the target can be reached from the source without the given clause and the
+    /// clauses that were deleted since the graph was built.
+    fn reaches(&self, formula: &Formula, source: usize, target: usize, without: usize, visited: &mut [bool], effort: &mut usize) -> bool {
+        let mut stack = vec![source];
+        let mut touched = vec![source];
+        visited[source] = true;
+        let mut found = false;
+        'search: while let Some(vertex) = stack.pop() {
+            for (successor, clause_index) in &self.edges[vertex] {
+                *effort = effort.saturating_sub(1);
+                if *clause_index == without || formula.clauses[*clause_index].deleted || visited[*successor] {
+                    continue;
+                }
+                if *successor == target {
+                    found = true;
+                    break 'search;
+                }
+                visited[*successor] = true;
+                touched.push(*successor);
+                stack.push(*successor);
+            }
+            if *effort == 0 {
+                break;
+            }
+        }
+        for vertex in touched {
+            visited[vertex] = false;
+        }
+        found
+    }
+}
+
+impl Formula {
+    /// Transitive reduction of the binary implication graph.
+    ///
+    /// A binary clause `a ∨ b` is redundant if `¬a` implies `b` over a path of other binary
+    /// clauses, so it is removed. Such clauses come from the encodings and the hyper-binary
+    /// resolution, they slow down the propagation without adding anything. Reasons are kept.
+    /// A round visits at most `EFFORT` edges and the next one continues with the following clause.
+    /// Returns the number of removed clauses.
+    pub(crate) fn reduce_transitive(&mut self) -> usize {
+        let graph = ImplicationGraph::new(self);
+        let mut binaries: Vec<usize> = (0..self.clauses.len())
+            .filter(|clause_index| !self.clauses[*clause_index].deleted && self.clauses[*clause_index].literals.len() == 2)
+            .collect();
+        let start = binaries.partition_point(|clause_index| *clause_index < self.transitive_position);
+        binaries.rotate_left(start);
+        let mut visited = vec![false; graph.edges.len()];
+        let mut effort = EFFORT;
+        let mut removed = 0;
+        for clause_index in binaries {
+            if effort == 0 {
+                break;
+            }
+            self.transitive_position = clause_index + 1;
+            if self.is_reason(clause_index) {
+                continue;
+            }
+            let [a, b] = self.clauses[clause_index].literals[..] else {
+                continue;
+            };
+            if graph.reaches(self, node(-a), node(b), clause_index, &mut visited, &mut effort) {
+                debug!(target: "reduce_transitive", "{:?} is implied by other binary clauses", [a, b]);
+                self.remove_clause(clause_index);
+                removed += 1;
+            }
+        }
+        removed
+    }
+}
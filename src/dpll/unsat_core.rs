@@ -0,0 +1,87 @@
+use crate::dpll::schemas::{ClauseType, Formula, FormulaResultType};
+use crate::utils::write_numbered_clauses;
+use log::debug;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The clauses every derived clause is resolved from, by clause id.
+/// It is filled with the same hints as an LRAT proof, the hints of the empty clause are kept
+/// separately because the empty clause is never added to the formula.
+#[derive(Debug, Default)]
+pub struct ResolutionTrail {
+    antecedents: HashMap<usize, Vec<usize>>,
+    conflict: Option<Vec<usize>>,
+}
+
+impl Formula {
+    /// Record the resolution trail of all following learned clauses, so an unsatisfiable core
+    /// can be extracted after the formula is found unsatisfiable.
+    pub fn enable_unsat_core(&mut self) {
+        self.resolution_trail = Some(ResolutionTrail::default());
+    }
+
+    pub(crate) fn record_antecedents(&mut self, id: usize, literals: &[i16], hints: &[usize]) {
+        if let Some(trail) = self.resolution_trail.as_mut() {
+            if literals.is_empty() {
+                trail.conflict = Some(hints.to_vec());
+            } else {
+                trail.antecedents.insert(id, hints.to_vec());
+            }
+        }
+    }
+
+    /// The DIMACS numbers of the original clauses that are unsatisfiable together, found by
+    /// following the antecedents of the empty clause back to the clauses of the input file.
+    /// Returns None if the formula is not unsatisfiable without assumptions or the trail was not recorded,
+    /// and an error if a learned or derived clause on the way has no antecedents.
+    pub fn unsat_core(&self) -> Result<Option<Vec<usize>>, String> {
+        if self.result != FormulaResultType::Unsatisfiable || !self.failed_assumptions.is_empty() {
+            return Ok(None);
+        }
+        let trail = match self.resolution_trail.as_ref() {
+            Some(trail) => trail,
+            None => return Ok(None),
+        };
+        let mut stack = match trail.conflict.clone() {
+            Some(conflict) => conflict,
+            None => return Ok(None),
+        };
+        let mut visited = vec![false; self.clauses.len()];
+        let mut core = Vec::new();
+        while let Some(id) = stack.pop() {
+            let clause_index = id - 1;
+            if visited[clause_index] {
+                continue;
+            }
+            visited[clause_index] = true;
+            if id <= self.original_clause_vector_length {
+                core.push(id);
+                continue;
+            }
+            // the resolvents of the preprocessing are derived clauses with antecedents, the clauses
+            // added after reading the file have no number in it and are left out
+            match trail.antecedents.get(&id) {
+                Some(antecedents) => stack.extend(antecedents.iter()),
+                None if self.clauses[clause_index].clause_type != ClauseType::Original => {
+                    return Err(format!("{:?} clause {} has no antecedents", self.clauses[clause_index].clause_type, id));
+                }
+                None => debug!(target: "unsat_core", "clause {} is not part of the input", id),
+            }
+        }
+        core.sort_unstable();
+        debug!(target: "unsat_core", "core with {} of {} original clauses", core.len(), self.original_clause_vector_length);
+        Ok(Some(core))
+    }
+
+    /// Write the unsatisfiable core as DIMACS CNF, every clause is preceded by its number in the original file.
+    pub fn write_unsat_core(&self, path: &PathBuf) -> Result<(), String> {
+        let clauses: Vec<(usize, Vec<i16>)> = self
+            .unsat_core()?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| (id, self.clauses[id - 1].literals.clone()))
+            .collect();
+        write_numbered_clauses(path, self.num_variables(), &clauses)
+            .map_err(|e| format!("can not write core file {:?}: {}", path, e))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::dpll::dpll::unit_propagation;
+use crate::dpll::probe::Prober;
+use crate::dpll::schemas::{Clause, ClauseType, Formula, FormulaResultType, Value};
+use log::debug;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Clause vivification on the root level.
+///
+/// The negations of the literals of a clause are assigned one after the other and propagated
+/// without the clause itself. If this leads to a conflict, the clause is shortened to the literals
+/// assigned so far. If a literal of the clause is implied true, the clause is shortened to the
+/// literals before and this one, and literals that are implied false are removed.
+/// The candidates are the learned clauses with the highest activity first, there are no tiers of
+/// learned clauses in this solver, and the original clauses if `vivify_original` is enabled.
+/// Every clause is tried once. The effort of a round is `vivify_effort` percent of the assignments
+/// of the search since the last round.
+pub(crate) fn vivify(formula: &mut Formula, timeout: Arc<AtomicBool>) -> Option<FormulaResultType> {
+    let budget = (formula.assignments - formula.last_vivification) * formula.preprocess.vivify_effort / 100;
+    formula.last_vivification = formula.assignments;
+    let mut candidates: Vec<usize> = (0..formula.clauses.len())
+        .filter(|clause_index| {
+            let clause = &formula.clauses[*clause_index];
+            !clause.deleted
+                && clause.literals.len() > 1
+                && (clause.clause_type == ClauseType::Learned || formula.preprocess.vivify_original)
+                && !formula.vivified.contains(clause_index)
+        })
+        .collect();
+    candidates.sort_by_key(|clause_index| {
+        let clause = &formula.clauses[*clause_index];
+        (clause.clause_type != ClauseType::Learned, Reverse(clause.activity))
+    });
+    let mut prober = Prober::new(formula.variables.len());
+    let mut shortened = 0;
+    for clause_index in candidates {
+        if prober.ticks >= budget || timeout.load(Ordering::SeqCst) {
+            break;
+        }
+        if formula.clauses[clause_index].deleted || formula.is_reason(clause_index) {
+            continue;
+        }
+        formula.vivified.insert(clause_index);
+        if let Some((literals, hints)) = vivify_clause(formula, &mut prober, clause_index) {
+            debug!(target: "vivify", "{:?} is shortened to {:?}", formula.clauses[clause_index].literals, &literals);
+            shortened += 1;
+            let unit = literals.len() == 1;
+            // the shortened clause is checked by the proof before the old one is deleted
+            let clause_type = match formula.clauses[clause_index].clause_type {
+                ClauseType::Learned => ClauseType::Learned,
+                _ => ClauseType::Derived,
+            };
+            formula.proof_add(Clause::id(formula.clauses.len()), &literals, &hints);
+            let result = formula.insert_clause(literals, clause_type);
+            formula.remove_clause(clause_index);
+            if let Some(result) = result {
+                formula.result = result;
+                return Some(result);
+            }
+            if unit && unit_propagation(formula).is_some() {
+                return Some(formula.result);
+            }
+        }
+    }
+    debug!(target: "vivify", "shortened {} clauses", shortened);
+    None
+}
+
+/// The shortened clause with its LRAT hints, if the clause can be shortened.
+fn vivify_clause(formula: &Formula, prober: &mut Prober, clause_index: usize) -> Option<(Vec<i16>, Vec<usize>)> {
+    let literals = formula.clauses[clause_index].literals.clone();
+    if literals.iter().any(|lit| formula.literal_value(*lit) == Value::True) {
+        return None;
+    }
+    prober.ignored = Some(clause_index);
+    let mut kept = Vec::new();
+    let mut result = None;
+    for lit in &literals {
+        match prober.value(formula, *lit) {
+            Value::True => {
+                kept.push(*lit);
+                result = Some(prober.hints(formula, &[*lit]));
+                break;
+            }
+            Value::False => {}
+            Value::Null => {
+                kept.push(*lit);
+                if let Err(conflict) = prober.propagate(formula, -lit) {
+                    let mut hints = prober.hints(formula, &formula.clauses[conflict].literals);
+                    hints.push(Clause::id(conflict));
+                    result = Some(hints);
+                    break;
+                }
+            }
+        }
+    }
+    // without a conflict all literals are false, so the clause itself is the conflict
+    let hints = result.unwrap_or_else(|| {
+        let mut hints = prober.hints(formula, &literals);
+        hints.push(Clause::id(clause_index));
+        hints
+    });
+    prober.undo();
+    prober.ignored = None;
+    (kept.len() < literals.len()).then_some((kept, hints))
+}
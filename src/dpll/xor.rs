@@ -0,0 +1,235 @@
+use crate::dpll::schemas::{ClauseType, Formula, Value};
+use log::{debug, warn};
+use std::collections::HashMap;
+
+/// A native XOR constraint: an odd number of its variables is true if the parity is true, an even
+/// number otherwise. Variables are indexes starting with 0.
+#[derive(Debug, Clone)]
+pub struct XorConstraint {
+    pub(crate) variables: Vec<usize>,
+    pub(crate) parity: bool,
+}
+
+/// The XOR constraints that share variables, as rows of a matrix over GF(2).
+/// Every row has one bit for every column, the columns are the variables of the constraints.
+#[derive(Debug, Clone)]
+pub(crate) struct GaussMatrix {
+    columns: Vec<usize>,
+    rows: Vec<(Vec<u64>, bool)>,
+}
+
+/// The result of one round of Gauss-Jordan elimination.
+#[derive(Debug, PartialEq)]
+pub(crate) enum XorPropagation {
+    // no XOR implies anything
+    Fixpoint,
+    // implied literals were pushed to the unit queue, their reasons are the new clauses
+    Units,
+    // the index of the new clause that is false under the assignment
+    Conflict(usize),
+}
+
+impl GaussMatrix {
+    fn new(constraints: &[&XorConstraint]) -> Self {
+        let mut columns: Vec<usize> = constraints.iter().flat_map(|xor| xor.variables.iter().copied()).collect();
+        columns.sort_unstable();
+        columns.dedup();
+        let column_of: HashMap<usize, usize> = columns.iter().enumerate().map(|(column, variable)| (*variable, column)).collect();
+        let words = columns.len().div_ceil(64);
+        let rows = constraints
+            .iter()
+            .map(|xor| {
+                let mut bits = vec![0u64; words];
+                for variable in &xor.variables {
+                    let column = column_of[variable];
+                    bits[column / 64] |= 1 << (column % 64);
+                }
+                (bits, xor.parity)
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    /// Eliminate the unassigned columns of a copy of the matrix. Every row of the result is the sum
+    /// of some original rows and still contains its assigned columns, so a row that has at most
+    /// one unassigned column left is a conflict or implies a literal, and its assigned columns are
+    /// the reason.
+    /// Returns the rows as (unassigned variables, assigned literals that are false, parity of the
+    /// unassigned variables).
+    fn eliminate(&self, formula: &Formula) -> Vec<(Vec<usize>, Vec<i16>, bool)> {
+        let mut rows = self.rows.clone();
+        let values: Vec<Value> = self.columns.iter().map(|variable| formula.variables[*variable].value).collect();
+        let bit = |row: &[u64], column: usize| row[column / 64] >> (column % 64) & 1 == 1;
+        let mut pivot_row = 0;
+        for (column, value) in values.iter().enumerate() {
+            if *value != Value::Null {
+                continue;
+            }
+            let Some(found) = (pivot_row..rows.len()).find(|row| bit(&rows[*row].0, column)) else {
+                continue;
+            };
+            rows.swap(pivot_row, found);
+            let (pivot_bits, pivot_parity) = rows[pivot_row].clone();
+            for (index, (bits, parity)) in rows.iter_mut().enumerate() {
+                if index != pivot_row && bit(bits, column) {
+                    for (word, pivot_word) in bits.iter_mut().zip(&pivot_bits) {
+                        *word ^= pivot_word;
+                    }
+                    *parity ^= pivot_parity;
+                }
+            }
+            pivot_row += 1;
+        }
+        rows.iter()
+            .map(|(bits, parity)| {
+                let mut unassigned = Vec::new();
+                let mut reason = Vec::new();
+                let mut parity = *parity;
+                for (column, variable) in self.columns.iter().enumerate() {
+                    if !bit(bits, column) {
+                        continue;
+                    }
+                    let literal = (*variable + 1) as i16;
+                    match values[column] {
+                        Value::Null => unassigned.push(*variable),
+                        Value::True => {
+                            parity = !parity;
+                            reason.push(-literal);
+                        }
+                        Value::False => reason.push(literal),
+                    }
+                }
+                (unassigned, reason, parity)
+            })
+            .collect()
+    }
+}
+
+impl Formula {
+    /// Add a native XOR constraint in the style of CryptoMiniSat: an odd number of the literals
+    /// has to be true, so `x1 -2 0` means that 1 and 2 are equal.
+    /// The constraint is not part of the open scopes, it can not be removed again.
+    pub fn add_xor_constraint(&mut self, literals: Vec<i16>) {
+        self.reset();
+        if !self.scopes.is_empty() {
+            warn!(target: "add_xor_constraint", "xor constraints are not removed with their scope");
+        }
+        let mut parity = true;
+        let mut variables: Vec<usize> = Vec::with_capacity(literals.len());
+        for lit in literals {
+            if lit == 0 {
+                continue;
+            }
+            let lit = self.internal_literal(lit);
+            parity ^= lit < 0;
+            let variable = (lit.unsigned_abs() - 1) as usize;
+            // a variable that occurs twice cancels out
+            match variables.iter().position(|v| *v == variable) {
+                Some(index) => {
+                    variables.swap_remove(index);
+                }
+                None => variables.push(variable),
+            }
+        }
+        debug!(target: "add_xor_constraint", "adding xor constraint: {:?} = {}", &variables, parity);
+        if variables.is_empty() {
+            if parity {
+                // the empty XOR is false
+                self.add_internal_clause(Vec::new());
+            }
+            return;
+        }
+        self.xor_constraints.push(XorConstraint { variables, parity });
+        self.gauss_matrices = None;
+    }
+
+    pub fn xor_constraints(&self) -> &[XorConstraint] {
+        &self.xor_constraints
+    }
+
+    pub(crate) fn is_xor_variable(&self, variable_index: usize) -> bool {
+        self.xor_constraints.iter().any(|xor| xor.variables.contains(&variable_index))
+    }
+
+    /// Split the XOR constraints into matrices that share no variable.
+    fn build_gauss_matrices(&self) -> Vec<GaussMatrix> {
+        let mut component_of: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for (index, xor) in self.xor_constraints.iter().enumerate() {
+            let mut joined: Vec<usize> = xor.variables.iter().filter_map(|v| component_of.get(v).copied()).collect();
+            joined.sort_unstable();
+            joined.dedup();
+            let target = match joined.first() {
+                Some(first) => *first,
+                None => {
+                    components.push(Vec::new());
+                    components.len() - 1
+                }
+            };
+            for other in joined.iter().skip(1) {
+                let moved = std::mem::take(&mut components[*other]);
+                for xor_index in &moved {
+                    for variable in &self.xor_constraints[*xor_index].variables {
+                        component_of.insert(*variable, target);
+                    }
+                }
+                components[target].extend(moved);
+            }
+            components[target].push(index);
+            for variable in &xor.variables {
+                component_of.insert(*variable, target);
+            }
+        }
+        components
+            .into_iter()
+            .filter(|component| !component.is_empty())
+            .map(|component| {
+                let constraints: Vec<&XorConstraint> = component.iter().map(|index| &self.xor_constraints[*index]).collect();
+                GaussMatrix::new(&constraints)
+            })
+            .collect()
+    }
+
+    /// Propagate the XOR constraints by Gauss-Jordan elimination on the current assignment.
+    /// The explanation of an implied literal or a conflict is added as learned clause, so the unit
+    /// propagation, backtracking and conflict analysis work on it like on every other clause.
+    pub(crate) fn gauss_propagate(&mut self) -> XorPropagation {
+        if self.xor_constraints.is_empty() {
+            return XorPropagation::Fixpoint;
+        }
+        if self.gauss_matrices.is_none() {
+            self.gauss_matrices = Some(self.build_gauss_matrices());
+        }
+        let matrices = self.gauss_matrices.take().unwrap();
+        let mut result = XorPropagation::Fixpoint;
+        'matrices: for matrix in &matrices {
+            for (unassigned, mut reason, parity) in matrix.eliminate(self) {
+                match unassigned.len() {
+                    0 if parity => {
+                        // the deepest literals are watched, so the clause is visited again after backtracking
+                        reason.sort_by_key(|lit| std::cmp::Reverse(self.variables[(lit.unsigned_abs() - 1) as usize].depth));
+                        debug!(target: "gauss_propagate", "xor conflict: {:?}", &reason);
+                        let clause_index = self.clauses.len();
+                        self.insert_clause(reason, ClauseType::Learned);
+                        result = XorPropagation::Conflict(clause_index);
+                        break 'matrices;
+                    }
+                    1 if self.variables[unassigned[0]].value == Value::Null => {
+                        let literal = (unassigned[0] + 1) as i16;
+                        reason.push(if parity { literal } else { -literal });
+                        debug!(target: "gauss_propagate", "xor implies {} by {:?}", reason.last().unwrap(), &reason);
+                        // inserting a learned unit clause clears the unit queue
+                        let mut units = std::mem::take(&mut self.units);
+                        self.insert_clause(reason, ClauseType::Learned);
+                        units.append(&mut self.units);
+                        self.units = units;
+                        result = XorPropagation::Units;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.gauss_matrices = Some(matrices);
+        result
+    }
+}
@@ -0,0 +1,2 @@
+pub mod dpll;
+pub mod utils;
@@ -1,18 +1,31 @@
 use crate::benchmark::benchmark;
-use crate::dpll::dpll as run_dpll;
-use crate::dpll::schemas::{Formula, HeuristicType};
 use crate::tests::{test, tests};
-use clap::{Parser, Subcommand};
+use dpll::dpll::approx::ApproxCounter;
+use dpll::dpll::checker::{read_cnf, read_proof, CheckResult, ProofChecker};
+use dpll::dpll::count::{parse_rational, ModelCounter};
+use dpll::dpll::dpll as run_dpll;
+use dpll::dpll::enumerate::ModelEnumerator;
+use dpll::dpll::gates::GateType;
+use dpll::dpll::groups::GroupedClauses;
+use dpll::dpll::local_search::{local_search, LocalSearchConfig, LocalSearchPhases, LocalSearchType};
+use dpll::dpll::mcs::McsEnumerator;
+use dpll::dpll::mus::MusExtractor;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::proof::{Proof, ProofFormat};
+use dpll::dpll::sample::Sampler;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType, SolveMode};
+use dpll::utils::{read_projection, read_weights, write_numbered_clauses};
+use clap::{Args, Parser, Subcommand};
 use log::info;
+use num_traits::{ToPrimitive, Zero};
+use std::fmt::Display;
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time;
+use std::{thread, time};
 
 mod benchmark;
-mod dpll;
 mod tests;
-mod utils;
 
 #[derive(Parser, Debug)]
 pub struct CommandLineArgs {
@@ -28,6 +41,90 @@ struct Cli {
     command: Commands,
 }
 
+/// The simplifications of the formula before and during the search, see [`PreprocessConfig`].
+#[derive(Args, Clone, Debug)]
+struct PreprocessArgs {
+    /// Preprocessing: replace the literals that are equivalent by the binary clauses with one
+    /// representative
+    #[arg(long)]
+    substitute: bool,
+    /// Preprocessing: remove subsumed clauses and strengthen clauses by self-subsuming resolution,
+    /// the learned clauses are simplified during the search as well
+    #[arg(long)]
+    subsume: bool,
+    /// Preprocessing: eliminate variables by clause distribution
+    #[arg(long)]
+    eliminate: bool,
+    /// Preprocessing: do not eliminate variables with more occurrences
+    #[arg(long, default_value_t = 20)]
+    occurrence_limit: usize,
+    /// Preprocessing: do not eliminate variables with longer resolvents
+    #[arg(long, default_value_t = 20)]
+    resolvent_limit: usize,
+    /// Preprocessing: eliminate variables that are defined by an and, xor, ite or equivalence gate
+    /// with the resolvents of the gate clauses only
+    #[arg(long)]
+    gates: bool,
+    /// Preprocessing: add the negation of failed literals and the literals implied by both
+    /// literals of a variable as units, this is repeated during the search
+    #[arg(long)]
+    probe: bool,
+    /// Preprocessing: the time limit of one probing round in milliseconds
+    #[arg(long, default_value_t = 100)]
+    probe_time: u64,
+    /// Preprocessing: add the hyper-binary resolvents of the probes as binary clauses
+    #[arg(long)]
+    hyper_binary: bool,
+    /// Preprocessing: remove blocked clauses
+    #[arg(long)]
+    blocked: bool,
+    /// Preprocessing: remove covered clauses, which includes the blocked ones
+    #[arg(long)]
+    covered: bool,
+    /// Preprocessing: replace products of literals and clauses by fewer clauses with new variables, which are not printed
+    #[arg(long)]
+    add_variables: bool,
+    /// CDCL: shorten the learned clauses by propagating the negations of their literals
+    #[arg(long)]
+    vivify: bool,
+    /// CDCL: vivify the original clauses as well
+    #[arg(long)]
+    vivify_original: bool,
+    /// CDCL: the effort of the vivification in percent of the assignments of the search
+    #[arg(long, default_value_t = 10)]
+    vivify_effort: usize,
+    /// CDCL: remove the binary clauses that are implied by a path of other binary clauses
+    #[arg(long)]
+    transitive_reduction: bool,
+    /// CDCL: the number of conflicts between two simplifications of the learned clauses
+    #[arg(long, default_value_t = 2000)]
+    inprocess_interval: usize,
+}
+
+impl PreprocessArgs {
+    fn config(&self) -> PreprocessConfig {
+        PreprocessConfig {
+            substitution: self.substitute,
+            subsumption: self.subsume,
+            elimination: self.eliminate,
+            occurrence_limit: self.occurrence_limit,
+            resolvent_limit: self.resolvent_limit,
+            gates: self.gates,
+            probing: self.probe,
+            probe_time: time::Duration::from_millis(self.probe_time),
+            hyper_binary: self.hyper_binary,
+            blocked: self.blocked,
+            covered: self.covered,
+            addition: self.add_variables,
+            vivification: self.vivify,
+            vivify_original: self.vivify_original,
+            vivify_effort: self.vivify_effort,
+            transitive_reduction: self.transitive_reduction,
+            inprocess_interval: self.inprocess_interval,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Subcommand)]
 enum Commands {
     /// run the test function
@@ -35,7 +132,10 @@ enum Commands {
     /// run the tests on the given directory
     Tests,
     /// runs the benchmark on the given directory, uses all of your cpu power
-    Benchmark,
+    Benchmark {
+        #[command(flatten)]
+        preprocess: PreprocessArgs,
+    },
     /// solve the given cnf file
     Solve {
         /// The file to run
@@ -43,9 +143,150 @@ enum Commands {
         /// The heuristic to use
         #[arg(value_enum)]
         heuristic: Option<HeuristicType>,
+        /// Write a DRAT or LRAT proof of the run to this file
+        #[arg(long)]
+        proof: Option<PathBuf>,
+        /// The format of the proof
+        #[arg(long, value_enum, default_value_t = ProofFormat::Text)]
+        proof_format: ProofFormat,
+        /// Write an unsatisfiable core with the numbers of the original clauses to this file
+        #[arg(long)]
+        core: Option<PathBuf>,
+        /// Solve with CDCL or with local search, which can only find models
+        #[arg(long, value_enum, default_value_t = SolveMode::Cdcl)]
+        mode: SolveMode,
+        /// The local search algorithm
+        #[arg(long, value_enum, default_value_t = LocalSearchType::ProbSat)]
+        local_search: LocalSearchType,
+        /// WalkSAT: the probability of a random flip
+        #[arg(long, default_value_t = 0.567)]
+        noise: f64,
+        /// ProbSAT: the base of the break function
+        #[arg(long, default_value_t = 2.38)]
+        cb: f64,
+        /// Local search: restart from a random assignment after this number of flips
+        #[arg(long, default_value_t = 1_000_000)]
+        restart_flips: usize,
+        /// Local search: give up after this number of restarts
+        #[arg(long)]
+        max_tries: Option<usize>,
+        /// Local search: the seed of the random choices
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// CDCL: run local search between the conflicts and decide the best assignment it finds
+        #[arg(long)]
+        local_search_phases: bool,
+        /// CDCL: the number of conflicts between two local search runs
+        #[arg(long, default_value_t = 2000)]
+        phase_interval: usize,
+        /// CDCL: the number of flips of one local search run
+        #[arg(long, default_value_t = 50_000)]
+        phase_flips: usize,
+        #[command(flatten)]
+        preprocess: PreprocessArgs,
+        /// Stop after this number of seconds and report UNKNOWN
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// check a DRAT or LRAT proof of unsatisfiability of the given cnf file
+    CheckProof {
+        /// The cnf file
+        cnf: PathBuf,
+        /// The proof to check
+        proof: PathBuf,
+        /// The format of the proof, detected from the content if not given (binary LRAT has to be given)
+        #[arg(long, value_enum)]
+        format: Option<ProofFormat>,
+        /// Write the original clauses that are needed for the proof to this file
+        #[arg(long)]
+        core: Option<PathBuf>,
+        /// Write the checked DRAT proof as LRAT proof to this file
+        #[arg(long)]
+        lrat: Option<PathBuf>,
+    },
+    /// find a minimal unsatisfiable subset of the clauses or groups of the given cnf or gcnf file
+    Mus {
+        /// The cnf or gcnf file
+        file: PathBuf,
+        /// Write the clauses of the subset with their clause or group numbers to this file
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Stop after this number of seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// enumerate the minimal correction subsets of the soft clauses or groups of the given cnf or gcnf file
+    Mcs {
+        /// The cnf or gcnf file, clauses of group 0 are hard
+        file: PathBuf,
+        /// Stop after this number of correction subsets
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Stop after this number of seconds
+        #[arg(long)]
+        timeout: Option<u64>,
+    },
+    /// enumerate all models of the given cnf file, projected onto the variables of its `c p show` lines
+    Enumerate {
+        /// The cnf file
+        file: PathBuf,
+        /// The heuristic to use
+        #[arg(value_enum)]
+        heuristic: Option<HeuristicType>,
+        /// Stop after this number of models
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// count the models of the given cnf file, projected onto the variables of its `c p show` lines
+    /// and weighted by its `c p weight` lines
+    Count {
+        /// The cnf file
+        file: PathBuf,
+        /// Read the literal weights from this file instead, with `c p weight 1 0.4 0` or `1 0.4` lines
+        #[arg(long)]
+        weights: Option<PathBuf>,
+        /// Compute the weighted count as floating point number instead of an exact fraction
+        #[arg(long)]
+        float: bool,
+        /// Estimate the unweighted count with random XOR constraints
+        #[arg(long, conflicts_with = "weights")]
+        approx: bool,
+        /// The tolerance of the estimate
+        #[arg(long, default_value_t = 0.8)]
+        epsilon: f64,
+        /// The probability that the estimate is not within the tolerance
+        #[arg(long, default_value_t = 0.2)]
+        delta: f64,
+        /// The seed of the random XOR constraints
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+    /// print the size of the given cnf file and the gates that define its variables
+    Stats {
+        /// The cnf file
+        file: PathBuf,
+    },
+    /// sample near uniform models of the given cnf file, projected onto the variables of its `c p show` lines
+    Sample {
+        /// The cnf file
+        file: PathBuf,
+        /// The number of samples
+        #[arg(short, long = "samples", default_value_t = 1)]
+        n: usize,
+        /// The seed of the random XOR constraints, the same seed gives the same samples
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
     },
 }
 
+/// The value of the result, or print the error and exit with a non-zero status.
+fn or_exit<T, E: Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        std::process::exit(1)
+    })
+}
+
 fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
@@ -57,15 +298,294 @@ fn main() {
     match args.command {
         Commands::Test => test(),
         Commands::Tests => tests(),
-        Commands::Benchmark => benchmark(),
-        Commands::Solve { file, heuristic } => {
+        Commands::Benchmark { preprocess } => benchmark(preprocess.config()),
+        Commands::Solve {
+            file,
+            heuristic,
+            proof,
+            proof_format,
+            core,
+            mode,
+            local_search: algorithm,
+            noise,
+            cb,
+            restart_flips,
+            max_tries,
+            seed,
+            local_search_phases,
+            phase_interval,
+            phase_flips,
+            preprocess,
+            timeout,
+        } => {
             let start = time::Instant::now();
+            let stop = Arc::new(AtomicBool::new(false));
+            if let Some(seconds) = timeout {
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    thread::sleep(time::Duration::from_secs(seconds));
+                    stop.store(true, Ordering::SeqCst);
+                });
+            }
             let mut formula = Formula::from_file(&file).unwrap();
             formula.heuristic_type = heuristic.unwrap_or(HeuristicType::None);
+            if (proof.is_some() || core.is_some()) && !formula.xor_constraints().is_empty() {
+                or_exit(Err::<(), _>("xor constraints are not supported with proofs and cores, their reasons are not derived"));
+            }
+            if let Some(path) = proof {
+                let proof = Proof::create(&path, proof_format).map_err(|e| format!("can not create proof file {:?}: {}", path, e));
+                formula.set_proof(or_exit(proof));
+            }
+            if core.is_some() {
+                formula.enable_unsat_core();
+            }
+            formula.preprocess = preprocess.config();
             //formula.update_score();
-            run_dpll::dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+            let config = LocalSearchConfig {
+                algorithm,
+                noise,
+                cb,
+                restart_flips,
+                max_tries,
+                seed,
+            };
+            match mode {
+                SolveMode::Cdcl => {
+                    if local_search_phases {
+                        formula.local_search_phases = Some(LocalSearchPhases {
+                            interval: phase_interval,
+                            flips: phase_flips,
+                            config,
+                        });
+                    }
+                    run_dpll::dpll(&mut formula, stop);
+                }
+                SolveMode::Local => local_search(&mut formula, config, stop),
+            }
+            formula.take_proof();
             info!("solved in {:?}", start.elapsed());
+            if let Some(path) = core {
+                if formula.result() == FormulaResultType::Unsatisfiable {
+                    or_exit(formula.write_unsat_core(&path));
+                }
+            }
             println!("{}", formula.write_solution());
+            if let Some(statistics) = formula.write_statistics() {
+                println!("{}", statistics);
+            }
+        }
+        Commands::CheckProof {
+            cnf,
+            proof,
+            format,
+            core,
+            lrat,
+        } => {
+            let start = time::Instant::now();
+            let mut checker = ProofChecker::new(or_exit(read_cnf(&cnf)));
+            let (format, steps) = or_exit(read_proof(&proof, format));
+            let result = match format {
+                ProofFormat::Text | ProofFormat::Binary => checker.check_drat(&steps),
+                ProofFormat::Lrat | ProofFormat::LratBinary => checker.check_lrat(&steps),
+            };
+            info!("checked in {:?}", start.elapsed());
+            match result {
+                CheckResult::Verified => {
+                    println!("s VERIFIED");
+                    if let Some(path) = core {
+                        or_exit(checker.write_core(&path).map_err(|e| format!("can not write core file {:?}: {}", path, e)));
+                    }
+                    if let Some(path) = lrat {
+                        if matches!(format, ProofFormat::Text | ProofFormat::Binary) {
+                            or_exit(checker.write_lrat(&path).map_err(|e| format!("can not write lrat file {:?}: {}", path, e)));
+                        } else {
+                            println!("c the proof is already an LRAT proof");
+                        }
+                    }
+                }
+                CheckResult::Invalid { lemma, literals } => {
+                    println!("c lemma {} can not be derived: {:?}", lemma, literals);
+                    println!("s NOT VERIFIED");
+                }
+                CheckResult::NoConflict => {
+                    println!("c the proof does not derive the empty clause");
+                    println!("s NOT VERIFIED");
+                }
+            }
+        }
+        Commands::Mus { file, output, timeout } => {
+            let start = time::Instant::now();
+            let clauses = or_exit(GroupedClauses::from_file(&file));
+            let stop = Arc::new(AtomicBool::new(false));
+            if let Some(seconds) = timeout {
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    thread::sleep(time::Duration::from_secs(seconds));
+                    stop.store(true, Ordering::SeqCst);
+                });
+            }
+            let mut extractor = MusExtractor::new(&clauses, stop);
+            let mus = extractor.find_mus();
+            info!(
+                "found in {:?} with {} solve calls, {} groups found by model rotation",
+                start.elapsed(),
+                extractor.solve_calls,
+                extractor.rotated_groups
+            );
+            match mus {
+                Err(FormulaResultType::Timeout) => println!("s UNKNOWN\nc Timeout"),
+                Err(_) => println!("s UNKNOWN"),
+                Ok(None) => println!("s SATISFIABLE"),
+                Ok(Some(groups)) => {
+                    println!("s UNSATISFIABLE");
+                    let numbers: Vec<String> = groups.iter().map(|group| group.to_string()).collect();
+                    println!("v {} 0", numbers.join(" "));
+                    if let Some(path) = output {
+                        let subset: Vec<(usize, Vec<i16>)> = clauses
+                            .clauses
+                            .iter()
+                            .filter(|(group, _)| *group == 0 || groups.contains(group))
+                            .cloned()
+                            .collect();
+                        write_numbered_clauses(&path, clauses.num_variables, &subset).expect("Can write mus file");
+                    }
+                }
+            }
+        }
+        Commands::Mcs { file, limit, timeout } => {
+            let start = time::Instant::now();
+            let clauses = or_exit(GroupedClauses::from_file(&file));
+            let stop = Arc::new(AtomicBool::new(false));
+            if let Some(seconds) = timeout {
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    thread::sleep(time::Duration::from_secs(seconds));
+                    stop.store(true, Ordering::SeqCst);
+                });
+            }
+            let mut enumerator = McsEnumerator::new(&clauses, stop);
+            let mut count = 0;
+            for mcs in enumerator.by_ref().take(limit.unwrap_or(usize::MAX)) {
+                let numbers: Vec<String> = mcs.iter().map(|group| group.to_string()).collect();
+                println!("v {} 0", numbers.join(" "));
+                count += 1;
+            }
+            info!("found in {:?} with {} solve calls", start.elapsed(), enumerator.solve_calls);
+            println!("c {} mcs found", count);
+            if enumerator.is_complete() {
+                println!("c all mcs found");
+            }
+        }
+        Commands::Enumerate { file, heuristic, limit } => {
+            let start = time::Instant::now();
+            let mut formula = Formula::from_file(&file).unwrap();
+            formula.heuristic_type = heuristic.unwrap_or(HeuristicType::None);
+            let projection = or_exit(read_projection(&file, formula.num_variables()));
+            let mut count = 0;
+            let enumerator = or_exit(ModelEnumerator::new(&mut formula, projection, Arc::new(AtomicBool::new(false))));
+            for model in enumerator.take(limit.unwrap_or(usize::MAX)) {
+                let literals: Vec<String> = model.iter().map(|lit| lit.to_string()).collect();
+                println!("v {} 0", literals.join(" "));
+                count += 1;
+            }
+            info!("enumerated in {:?}", start.elapsed());
+            println!("c {} models", count);
+        }
+        Commands::Count { file, weights, float, approx, epsilon, delta, seed } => {
+            let start = time::Instant::now();
+            if approx {
+                let clauses = or_exit(GroupedClauses::from_file(&file));
+                let projection = or_exit(read_projection(&file, clauses.num_variables));
+                let prefix = if projection.is_some() { "p" } else { "" };
+                let num_variables = clauses.num_variables;
+                let clauses = clauses.clauses.into_iter().map(|(_, literals)| literals).collect();
+                let mut counter = or_exit(ApproxCounter::new(num_variables, clauses, projection, epsilon, delta, seed));
+                let count = counter.count();
+                info!("counted in {:?} with {} cell queries", start.elapsed(), counter.cell_queries);
+                println!("{}", if count.is_zero() { "s UNSATISFIABLE" } else { "s SATISFIABLE" });
+                println!("c s type {}mc", prefix);
+                println!("c s approx arb int {}", count);
+                return;
+            }
+            let clauses = or_exit(GroupedClauses::from_file(&file));
+            let projection = or_exit(read_projection(&file, clauses.num_variables));
+            let prefix = if projection.is_some() { "p" } else { "" };
+            let num_variables = clauses.num_variables;
+            let weights = or_exit(match weights {
+                Some(path) => read_weights(&path, true, num_variables),
+                None => read_weights(&file, false, num_variables),
+            });
+            let clauses: Vec<Vec<i16>> = clauses.clauses.into_iter().map(|(_, literals)| literals).collect();
+            let (zero, count_type, count) = if weights.is_empty() {
+                let count = or_exit(ModelCounter::new(num_variables, clauses, projection)).count();
+                (count.is_zero(), "mc", format!("exact arb int {}", count))
+            } else if float {
+                let weights = or_exit(
+                    weights
+                        .into_iter()
+                        .map(|(lit, weight)| match parse_rational(&weight).and_then(|w| w.to_f64()) {
+                            Some(w) => Ok((lit, w)),
+                            None => Err(format!("invalid weight: {}", weight)),
+                        })
+                        .collect::<Result<Vec<_>, _>>(),
+                );
+                let mut counter = or_exit(ModelCounter::new(num_variables, clauses, projection).and_then(|counter| counter.with_weights(weights)));
+                let count = counter.count();
+                (count.is_zero(), "wmc", format!("exact double float {}", count))
+            } else {
+                let weights = or_exit(
+                    weights
+                        .into_iter()
+                        .map(|(lit, weight)| match parse_rational(&weight) {
+                            Some(w) => Ok((lit, w)),
+                            None => Err(format!("invalid weight: {}", weight)),
+                        })
+                        .collect::<Result<Vec<_>, _>>(),
+                );
+                let mut counter = or_exit(ModelCounter::new(num_variables, clauses, projection).and_then(|counter| counter.with_weights(weights)));
+                let count = counter.count();
+                (count.is_zero(), "wmc", format!("exact arb frac {}", count))
+            };
+            info!("counted in {:?}", start.elapsed());
+            println!("{}", if zero { "s UNSATISFIABLE" } else { "s SATISFIABLE" });
+            println!("c s type {}{}", prefix, count_type);
+            println!("c s {}", count);
+        }
+        Commands::Stats { file } => {
+            let formula = Formula::from_file(&file).unwrap();
+            let gates = formula.gates();
+            println!("c variables: {}", formula.variables().len());
+            println!("c clauses: {}", formula.clauses().len());
+            for (name, gate_type) in [
+                ("equivalence", GateType::Equivalence),
+                ("and", GateType::And),
+                ("ite", GateType::Ite),
+                ("xor", GateType::Xor),
+            ] {
+                println!("c {} gates: {}", name, gates.iter().filter(|gate| gate.gate_type == gate_type).count());
+            }
+        }
+        Commands::Sample { file, n, seed } => {
+            let start = time::Instant::now();
+            let clauses = or_exit(GroupedClauses::from_file(&file));
+            let projection = or_exit(read_projection(&file, clauses.num_variables));
+            let num_variables = clauses.num_variables;
+            let clauses = clauses.clauses.into_iter().map(|(_, literals)| literals).collect();
+            let mut sampler = Sampler::new(num_variables, clauses, projection, seed);
+            let mut count = 0;
+            for model in sampler.by_ref().take(n) {
+                if count == 0 {
+                    println!("s SATISFIABLE");
+                }
+                let literals: Vec<String> = model.iter().map(|lit| lit.to_string()).collect();
+                println!("v {} 0", literals.join(" "));
+                count += 1;
+            }
+            info!("sampled in {:?} with {} cell queries", start.elapsed(), sampler.cell_queries);
+            if count == 0 && n > 0 {
+                println!("{}", if sampler.is_unsatisfiable() { "s UNSATISFIABLE" } else { "s UNKNOWN" });
+            }
+            println!("c {} samples", count);
         }
     }
 }
@@ -1,8 +1,8 @@
-use crate::dpll::dpll;
-use crate::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
-use crate::utils::plot_data;
+use dpll::dpll::dpll as run_dpll;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use dpll::utils::plot_data;
 use log::{info, warn};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
@@ -13,27 +13,27 @@ pub fn test() {
     //let path = PathBuf::from("data/inputs/test\\sat\\count4_2.cnf");
     let path = PathBuf::from("data/inputs/test\\unsat\\op5.cnf");
     info!("Formula {:?}", path);
-    let mut formula = Formula::from_file(&PathBuf::from(path)).unwrap();
+    let mut formula = Formula::from_file(&path).unwrap();
     formula.heuristic_type = HeuristicType::None;
     //formula.update_score();
-    dpll::dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    run_dpll::dpll(&mut formula, Arc::new(AtomicBool::new(false)));
 
-    for clause in formula.clauses.iter() {
+    for clause in formula.clauses().iter() {
         info!("Clause: {:?}", clause);
     }
 
-    for variable in formula.variables.iter() {
+    for variable in formula.variables().iter() {
         info!("Variable: {:?}", variable);
     }
 
-    info!("Result: {:?}", formula.result);
+    info!("Result: {:?}", formula.result());
     info!("Time: {:?}", start.elapsed());
 }
 
 pub fn tests() {
     let mut data = Vec::new();
 
-    for heuristic in vec![
+    for heuristic in [
         HeuristicType::None,
         /*HeuristicType::DLIS,
         HeuristicType::DLCS,
@@ -56,7 +56,7 @@ pub fn tests() {
             };
             for path in fs::read_dir(dir).unwrap() {
                 let path = path.unwrap().path();
-                if path == PathBuf::from("data/inputs/test\\sat\\unique.cnf")
+                if path.as_path() == Path::new("data/inputs/test\\sat\\unique.cnf")
                 {
                     warn!("Skipping {:?}", path);
                     continue;
@@ -67,9 +67,9 @@ pub fn tests() {
                 formula.heuristic_type = heuristic;
                 //formula.update_score();
 
-                dpll::dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+                run_dpll::dpll(&mut formula, Arc::new(AtomicBool::new(false)));
                 info!("Result: {}", Formula::write_solution(&formula));
-                assert_eq!(formula.result, excepted);
+                assert_eq!(formula.result(), excepted);
                 let time = start.elapsed();
                 info!("Time: {:?}", time);
                 times.push(time);
@@ -1,5 +1,6 @@
+use crate::dpll::preprocess::PreprocessConfig;
 use crate::dpll::schemas::{
-    Clause, ClauseType, Formula, FormulaResultType, HeuristicType, Value, Variable,
+    Clause, ClauseType, Formula, FormulaResultType, HeuristicType, Statistics, Value, Variable,
 };
 use log::warn;
 use plotters::backend::BitMapBackend;
@@ -10,13 +11,14 @@ use plotters::prelude::{IntoFont, LineSeries, BLACK, BLUE, CYAN, GREEN, MAGENTA,
 use plotters::style::Color;
 use std::collections::{HashSet, VecDeque};
 use std::fs::File;
-use std::io::Read;
+use std::fmt::Display;
+use std::io::{BufWriter, Read, Write};
 use std::path::PathBuf;
 use std::time::Duration;
 
 impl Variable {
     /// Create new variable
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             value: Value::Null,
             watched_neg_occurrences: HashSet::new(),
@@ -26,13 +28,14 @@ impl Variable {
             score: 0.0,
             depth: 0,
             reason: None,
+            phase: true,
         }
     }
 }
 
 impl Clause {
     /// Read line and convert it to Clause
-    fn create_clause(s: &str, variables: &mut Vec<Variable>, clause_index: usize) -> Self {
+    fn create_clause(s: &str, variables: &mut [Variable], clause_index: usize) -> Self {
         let mut literals_set: HashSet<i16> = HashSet::new();
         // Split as part by whitespace
         let parts: Vec<&str> = s.split_whitespace().collect();
@@ -46,7 +49,7 @@ impl Clause {
                 continue;
             }
             literals_set.insert(lit);
-            let var = lit.abs() as usize;
+            let var = lit.unsigned_abs() as usize;
             if lit > 0 {
                 // DIMACS CNF format's variables are numbered from 1
                 // but the variables are numbered from 0
@@ -57,8 +60,7 @@ impl Clause {
         }
 
         let literals: Vec<i16> = literals_set.into_iter().collect();
-        let watched;
-        if literals.len() == 1 {
+        let watched = if literals.len() == 1 {
             let lit = literals[0];
             if lit > 0 {
                 variables[(literals[0].abs() - 1) as usize]
@@ -69,7 +71,7 @@ impl Clause {
                     .watched_neg_occurrences
                     .insert(clause_index);
             }
-            watched = (0, 0);
+            (0, 0)
         } else {
             let lit = literals[0];
             if lit > 0 {
@@ -91,14 +93,15 @@ impl Clause {
                     .watched_neg_occurrences
                     .insert(clause_index);
             }
-            watched = (0, 1);
-        }
+            (0, 1)
+        };
 
         Self {
             literals,
             watched,
             clause_type: ClauseType::Original,
             activity: 0,
+            deleted: false,
         }
     }
 }
@@ -116,6 +119,7 @@ impl Formula {
         let mut variables = Vec::new();
 
         let mut current_literals = String::new();
+        let mut xor_constraints = Vec::new();
 
         for line in contents.lines() {
             let line = line.trim();
@@ -133,6 +137,13 @@ impl Formula {
                 let num_clauses = parts[3].parse::<u16>().expect("Can parse num clauses");
                 clauses = Vec::with_capacity(num_clauses as usize);
                 variables = vec![Variable::new(); num_vars as usize];
+            } else if let Some(xor) = line.strip_prefix('x') {
+                // XOR constraints in the style of CryptoMiniSat: x1 -2 3 0
+                let literals: Vec<i16> = xor
+                    .split_whitespace()
+                    .map(|part| part.parse::<i16>().expect("Can parse number"))
+                    .collect();
+                xor_constraints.push(literals);
             } else if line.trim() == "0" {
                 let clause = Clause::create_clause(
                     current_literals.clone().as_str(),
@@ -151,17 +162,32 @@ impl Formula {
             }
         }
 
-        if clauses.is_empty() || variables.is_empty() {
+        if clauses.is_empty() && xor_constraints.is_empty() || variables.is_empty() {
             return Err("file is empty");
         }
 
+        let mut formula = Self::from_parts(clauses, variables);
+        for literals in xor_constraints {
+            formula.add_xor_constraint(literals);
+        }
+        Ok(formula)
+    }
+
+    /// Create an empty formula over the given number of variables, clauses can be added with
+    /// [`add_original_clause`](Formula::add_original_clause).
+    pub fn new(num_variables: usize) -> Self {
+        Self::from_parts(Vec::new(), vec![Variable::new(); num_variables])
+    }
+
+    fn from_parts(clauses: Vec<Clause>, variables: Vec<Variable>) -> Self {
         let variables_index = variables
             .iter()
             .enumerate()
             .map(|(index, _)| (index, 0.0))
             .collect::<Vec<(usize, f32)>>();
         let original_clause_vector_length = clauses.len();
-        Ok(Self {
+        let user_variables = (0..variables.len()).collect();
+        Self {
             assigment_stack: Vec::with_capacity(variables.len()),
             clauses,
             variables,
@@ -171,24 +197,33 @@ impl Formula {
             heuristic_type: HeuristicType::None,
             original_clause_vector_length,
             depth: 0,
-        })
+            scopes: Vec::new(),
+            assumptions: Vec::new(),
+            failed_assumptions: Vec::new(),
+            user_variables,
+            proof: None,
+            resolution_trail: None,
+            xor_constraints: Vec::new(),
+            gauss_matrices: None,
+            conflicts: 0,
+            local_search_phases: None,
+            preprocess: PreprocessConfig::default(),
+            preprocessed: false,
+            reconstruction: Vec::new(),
+            probe_position: 0,
+            assignments: 0,
+            last_vivification: 0,
+            vivified: HashSet::new(),
+            transitive_position: 0,
+            statistics: Statistics::default(),
+        }
     }
 
     pub fn write_solution(&self) -> String {
         let solution = match self.result {
             FormulaResultType::Satisfiable => {
-                let literals: Vec<String> = self
-                    .variables
-                    .iter()
-                    .enumerate()
-                    .map(|(index, var)| {
-                        if var.value == Value::True {
-                            (index + 1).to_string()
-                        } else {
-                            (-((index + 1) as i32)).to_string()
-                        }
-                    })
-                    .collect();
+                let literals: Vec<String> =
+                    self.model().unwrap_or_default().iter().map(|lit| lit.to_string()).collect();
                 format!("s SATISFIABLE\nv {}", literals.join(" "))
             }
             FormulaResultType::Unsatisfiable => "s UNSATISFIABLE".to_string(),
@@ -197,10 +232,92 @@ impl Formula {
         };
         solution
     }
+
+    /// The counts of the simplifications that happened as comment lines, None if there were none.
+    pub fn write_statistics(&self) -> Option<String> {
+        let counts = [
+            ("hyper-binary resolvents", self.statistics.hyper_binary_resolvents),
+            ("transitive reductions", self.statistics.transitive_reductions),
+            ("gate eliminations", self.statistics.gate_eliminations),
+        ];
+        let lines: Vec<String> = counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(name, count)| format!("c {}: {}", name, count))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// Read the projection variables of the `c p show 1 2 3 0` lines of a DIMACS file.
+/// Returns None if the file has no such line, the variables of several lines are joined.
+/// Every variable has to be one of the `num_variables` variables of the formula.
+pub fn read_projection(filename: &PathBuf, num_variables: usize) -> Result<Option<Vec<i16>>, String> {
+    let contents = std::fs::read_to_string(filename).map_err(|e| format!("can not read {:?}: {}", filename, e))?;
+    let mut projection: Option<Vec<i16>> = None;
+    for line in contents.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 3 || parts[..3] != ["c", "p", "show"] {
+            continue;
+        }
+        let variables = projection.get_or_insert_with(Vec::new);
+        for part in &parts[3..] {
+            let variable = part.parse::<i16>().map_err(|_| format!("invalid projection variable: {}", part))?;
+            if variable < 0 || variable as usize > num_variables {
+                return Err(format!("projection variable {} is not a variable of the formula", variable));
+            }
+            if variable != 0 && !variables.contains(&variable) {
+                variables.push(variable);
+            }
+        }
+    }
+    Ok(projection)
+}
+
+/// Read literal weights from the `c p weight 1 0.4 0` lines of a DIMACS file. A side file may also
+/// contain plain `1 0.4` lines. The weights are returned as text, so they can be parsed exactly.
+/// Every literal has to belong to one of the `num_variables` variables of the formula.
+pub fn read_weights(filename: &PathBuf, side_file: bool, num_variables: usize) -> Result<Vec<(i16, String)>, String> {
+    let contents = std::fs::read_to_string(filename).map_err(|e| format!("can not read {:?}: {}", filename, e))?;
+    let mut weights = Vec::new();
+    for line in contents.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let parts = if parts.len() >= 5 && parts[..3] == ["c", "p", "weight"] {
+            &parts[3..]
+        } else if side_file && parts.len() >= 2 && !parts[0].starts_with('c') {
+            &parts[..]
+        } else {
+            continue;
+        };
+        let literal = parts[0].parse::<i16>().map_err(|_| format!("invalid weighted literal: {}", parts[0]))?;
+        if literal == 0 || literal.unsigned_abs() as usize > num_variables {
+            return Err(format!("weighted literal {} is not a literal of the formula", literal));
+        }
+        weights.push((literal, parts[1].to_string()));
+    }
+    Ok(weights)
+}
+
+/// Write clauses as DIMACS CNF, every clause is preceded by a comment with its number.
+pub fn write_numbered_clauses<T: Display>(
+    path: &PathBuf,
+    num_variables: usize,
+    clauses: &[(usize, Vec<T>)],
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "p cnf {} {}", num_variables, clauses.len())?;
+    for (number, literals) in clauses {
+        writeln!(writer, "c {}", number)?;
+        for lit in literals {
+            write!(writer, "{} ", lit)?;
+        }
+        writeln!(writer, "0")?;
+    }
+    writer.flush()
 }
 
 pub fn plot_data(
-    data: &Vec<(HeuristicType, Vec<Duration>)>,
+    data: &[(HeuristicType, Vec<Duration>)],
     num_of_problems: i32,
     name: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -258,12 +375,12 @@ pub fn plot_data(
             )
             .unwrap()
             .label(name)
-            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.clone()));
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
     }
     chart
         .configure_series_labels()
-        .border_style(&BLACK)
-        .background_style(&WHITE.mix(0.8))
+        .border_style(BLACK)
+        .background_style(WHITE.mix(0.8))
         .label_font(("sans-serif", 20).into_font())
         .draw()
         .unwrap();
@@ -0,0 +1,81 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+// every variable of the range is false pairwise
+fn at_most_one(variables: std::ops::RangeInclusive<i16>) -> Vec<Vec<i16>> {
+    let variables: Vec<i16> = variables.collect();
+    let mut clauses = Vec::new();
+    for (index, first) in variables.iter().enumerate() {
+        for second in &variables[index + 1..] {
+            clauses.push(vec![-first, -second]);
+        }
+    }
+    clauses
+}
+
+fn adding_formula(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig { addition: true, ..PreprocessConfig::default() };
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula
+}
+
+fn has_added_variable(formula: &Formula, num_variables: usize) -> bool {
+    formula
+        .clauses()
+        .iter()
+        .any(|clause| !clause.deleted && clause.literals().iter().any(|lit| lit.unsigned_abs() as usize > num_variables))
+}
+
+#[test]
+fn pairwise_encodings_get_smaller() {
+    let mut clauses = at_most_one(1..=8);
+    clauses.push((1..=8).collect());
+    let formula = adding_formula(8, &clauses);
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    // the added variables are internal to the solver
+    assert_eq!(formula.num_variables(), 8);
+    assert!(has_added_variable(&formula, 8));
+    assert!(formula.clauses().iter().filter(|clause| !clause.deleted).count() < clauses.len());
+    // the added variables are not part of the model
+    let model = formula.model().unwrap();
+    assert_eq!(model.len(), 8);
+    assert_eq!(model.iter().filter(|lit| **lit > 0).count(), 1);
+}
+
+#[test]
+fn added_variables_keep_the_formula_unsatisfiable() {
+    // five pigeons in four holes, pigeon p is in hole h if 4 * p + h + 1 is true
+    let mut clauses = Vec::new();
+    for hole in 0..4 {
+        let pigeons = at_most_one(1..=5).into_iter().map(|clause| clause.iter().map(|lit| lit.signum() * (4 * (lit.abs() - 1) + hole + 1)).collect());
+        clauses.extend(pigeons);
+    }
+    for pigeon in 0..5 {
+        clauses.push((1..=4).map(|hole| 4 * pigeon + hole).collect());
+    }
+    let formula = adding_formula(20, &clauses);
+    assert_eq!(formula.num_variables(), 20);
+    assert!(has_added_variable(&formula, 20));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+
+    // the clauses of the added variables are followed back to the clauses they replace
+    let path = std::env::temp_dir().join("dpll_addition_test_core.cnf");
+    let lines: Vec<String> = clauses.iter().map(|clause| format!("{} 0", clause.iter().map(|lit| lit.to_string()).collect::<Vec<_>>().join(" "))).collect();
+    std::fs::write(&path, format!("p cnf 20 {}\n{}\n", clauses.len(), lines.join("\n"))).unwrap();
+    let mut formula = Formula::from_file(&path).unwrap();
+    formula.preprocess = PreprocessConfig { addition: true, ..PreprocessConfig::default() };
+    formula.enable_unsat_core();
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert!(has_added_variable(&formula, 20));
+    let core = formula.unsat_core().unwrap().unwrap();
+    assert!(!core.is_empty() && core.iter().all(|id| *id <= clauses.len()));
+    let core_formula = adding_formula(20, &core.iter().map(|id| clauses[id - 1].clone()).collect::<Vec<_>>());
+    assert_eq!(core_formula.result(), FormulaResultType::Unsatisfiable);
+}
@@ -0,0 +1,30 @@
+use dpll::dpll::approx::ApproxCounter;
+use num_bigint::BigUint;
+
+#[test]
+fn approx_count_small_formula_exactly() {
+    // less models than the threshold are counted without hashing
+    let mut counter = ApproxCounter::new(3, vec![vec![1, 2], vec![-3, 3]], None, 0.8, 0.2, 1).unwrap();
+    assert_eq!(counter.count(), BigUint::from(6u32));
+    assert_eq!(counter.cell_queries, 1);
+}
+
+#[test]
+fn approx_count_within_tolerance() {
+    // 1 or 2 or 3 with 7 free variables has 7 * 2^7 = 896 models, projected onto 1..=8 there are 224
+    let clauses = vec![vec![1, 2, 3]];
+    let mut counter = ApproxCounter::new(10, clauses.clone(), None, 0.8, 0.2, 7).unwrap();
+    let count = counter.count();
+    assert!(count >= BigUint::from(896u32 * 5 / 9) && count <= BigUint::from(896u32 * 9 / 5), "{}", count);
+
+    let mut counter = ApproxCounter::new(10, clauses, Some((1..=8).collect()), 0.8, 0.2, 7).unwrap();
+    let count = counter.count();
+    assert!(count >= BigUint::from(224u32 * 5 / 9) && count <= BigUint::from(224u32 * 9 / 5), "{}", count);
+}
+
+#[test]
+fn approx_count_rejects_invalid_tolerance() {
+    assert!(ApproxCounter::new(3, vec![vec![1, 2]], None, 0.0, 0.2, 1).is_err());
+    assert!(ApproxCounter::new(3, vec![vec![1, 2]], None, 0.8, 0.0, 1).is_err());
+    assert!(ApproxCounter::new(3, vec![vec![1, 2]], None, 0.8, 1.0, 1).is_err());
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{assert_model_satisfies, solve_with};
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use std::path::PathBuf;
+
+const HEURISTICS: [HeuristicType; 5] =
+    [HeuristicType::None, HeuristicType::MOM, HeuristicType::DLIS, HeuristicType::DLCS, HeuristicType::JeroslowWang];
+
+// the restarts of the inprocessing go back to the root level a lot, which the backjumps have to survive
+fn solve(path: &str, heuristic: HeuristicType) -> Formula {
+    let preprocess = PreprocessConfig { elimination: true, probing: true, inprocess_interval: 5, ..PreprocessConfig::default() };
+    solve_with(path, heuristic, preprocess)
+}
+
+#[test]
+fn learned_clauses_do_not_refute_satisfiable_formulas() {
+    let original = Formula::from_file(&PathBuf::from("data/inputs/test/sat/hole5_5.cnf")).unwrap();
+    for heuristic in HEURISTICS {
+        let formula = solve("data/inputs/test/sat/hole5_5.cnf", heuristic);
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable, "{:?}", heuristic);
+        assert_model_satisfies(&original, &formula.model().unwrap());
+    }
+}
+
+#[test]
+fn unsatisfiable_formulas_stay_unsatisfiable() {
+    for heuristic in HEURISTICS {
+        let formula = solve("data/inputs/test/unsat/hole5.cnf", heuristic);
+        assert_eq!(formula.result(), FormulaResultType::Unsatisfiable, "{:?}", heuristic);
+    }
+}
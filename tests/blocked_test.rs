@@ -0,0 +1,36 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn solve(clauses: &[Vec<i16>], preprocess: PreprocessConfig) -> Vec<Vec<i16>> {
+    let mut formula = Formula::new(4);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = preprocess;
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+    formula.clauses().iter().filter(|clause| !clause.deleted).map(|clause| clause.literals().to_vec()).collect()
+}
+
+#[test]
+fn blocked_clauses_are_removed() {
+    // [1, -2] and [-1, 2] are blocked on 1 and -1, their resolvents on it are tautologies
+    let clauses = vec![vec![1, -2], vec![-1, 2], vec![2, 3, -4], vec![-3, 4], vec![-2, -3]];
+    let remaining = solve(&clauses, PreprocessConfig { blocked: true, ..PreprocessConfig::default() });
+    assert!(remaining.len() < clauses.len());
+}
+
+#[test]
+fn covered_clauses_are_removed() {
+    // [1, 2] is not blocked, but it is covered by 3 on 1 and then blocked on 3
+    let clauses = vec![vec![1, 2], vec![-1, 3], vec![-2, 3], vec![-3, -1, -2], vec![1, 4], vec![2, -4]];
+    let remaining = solve(&clauses, PreprocessConfig { blocked: true, ..PreprocessConfig::default() });
+    assert_eq!(remaining.len(), clauses.len());
+    let remaining = solve(&clauses, PreprocessConfig { covered: true, ..PreprocessConfig::default() });
+    assert!(!remaining.contains(&vec![1, 2]));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::assert_proof_verifies;
+use dpll::dpll::checker::{read_proof, CheckResult, ProofChecker, ProofStep};
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::proof::{Proof, ProofFormat};
+use dpll::dpll::schemas::Formula;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn solve_with_proof(cnf: &PathBuf, format: ProofFormat, name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dpll_checker_test_{}", name));
+    let mut formula = Formula::from_file(cnf).unwrap();
+    formula.set_proof(Proof::create(&path, format).unwrap());
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula.take_proof();
+    path
+}
+
+fn add(literals: Vec<i32>) -> ProofStep {
+    ProofStep::Add {
+        id: 0,
+        literals,
+        hints: Vec::new(),
+    }
+}
+
+#[test]
+fn solver_proofs_are_verified() {
+    let cnf = PathBuf::from("data/inputs/test/unsat/hole5.cnf");
+    for (format, name) in [(ProofFormat::Text, "drat"), (ProofFormat::Lrat, "lrat")] {
+        let path = solve_with_proof(&cnf, format, name);
+        assert_eq!(read_proof(&path, None).unwrap().0, format);
+        assert!(!assert_proof_verifies(&cnf, &path, format).core().is_empty());
+
+        // a comment with letters does not make a text proof look binary
+        let proof = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, format!("c proof of hole5, written by dpll\n{}", proof)).unwrap();
+        assert_eq!(read_proof(&path, None).unwrap().0, format);
+    }
+}
+
+#[test]
+fn rat_lemmas_and_invalid_lemmas() {
+    let clauses = vec![vec![1, 2], vec![-1, 2], vec![1, -2], vec![-1, -2]];
+    // -3 is no RUP lemma, but every resolution on 3 is
+    let proof = vec![add(vec![3, 1]), add(vec![3, -1]), add(vec![-3]), add(vec![])];
+    assert_eq!(ProofChecker::new(clauses.clone()).check_drat(&proof), CheckResult::Verified);
+
+    // both units are RAT alone, but not together
+    let proof = vec![add(vec![3]), add(vec![-3]), add(vec![])];
+    assert_eq!(
+        ProofChecker::new(clauses).check_drat(&proof),
+        CheckResult::Invalid {
+            lemma: 2,
+            literals: vec![-3]
+        }
+    );
+}
+
+#[test]
+fn binary_proofs_are_verified() {
+    let cnf = PathBuf::from("data/inputs/test/unsat/hole5.cnf");
+    // binary LRAT proofs can not be told apart from binary DRAT proofs, so their format is given
+    let formats = [(ProofFormat::Binary, None, "drat_binary"), (ProofFormat::LratBinary, Some(ProofFormat::LratBinary), "lrat_binary")];
+    for (format, given, name) in formats {
+        let path = solve_with_proof(&cnf, format, name);
+        assert_eq!(read_proof(&path, given).unwrap().0, format, "{}", name);
+        assert_proof_verifies(&cnf, &path, format);
+    }
+}
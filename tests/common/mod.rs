@@ -0,0 +1,39 @@
+// every test file uses only some of the helpers
+#![allow(dead_code)]
+
+use dpll::dpll::checker::{read_cnf, read_proof, CheckResult, ProofChecker};
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::proof::ProofFormat;
+use dpll::dpll::schemas::{Formula, HeuristicType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Solve the file with the heuristic and the preprocessing.
+pub fn solve_with(path: &str, heuristic: HeuristicType, preprocess: PreprocessConfig) -> Formula {
+    let mut formula = Formula::from_file(&PathBuf::from(path)).unwrap();
+    formula.heuristic_type = heuristic;
+    formula.preprocess = preprocess;
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula
+}
+
+/// Every clause of the original formula has a literal of the model.
+pub fn assert_model_satisfies(original: &Formula, model: &[i16]) {
+    for clause in original.clauses() {
+        assert!(clause.literals().iter().any(|lit| model.contains(lit)), "{:?} is not satisfied", clause.literals());
+    }
+}
+
+/// Check the proof of the formula in the file, the checker is returned for its core.
+pub fn assert_proof_verifies(cnf: &PathBuf, proof: &PathBuf, format: ProofFormat) -> ProofChecker {
+    let (_, steps) = read_proof(proof, Some(format)).unwrap();
+    let mut checker = ProofChecker::new(read_cnf(cnf).unwrap());
+    let result = match format {
+        ProofFormat::Lrat | ProofFormat::LratBinary => checker.check_lrat(&steps),
+        _ => checker.check_drat(&steps),
+    };
+    assert_eq!(result, CheckResult::Verified, "{:?} with {:?}", proof, format);
+    checker
+}
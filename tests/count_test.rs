@@ -0,0 +1,60 @@
+use dpll::dpll::count::{parse_rational, ModelCounter};
+use dpll::utils::read_weights;
+use num_bigint::BigUint;
+use num_rational::BigRational;
+
+#[test]
+fn count_models() {
+    // 1 or 2 has 3 models, together with the free variables 3 and 4 there are 12
+    let mut counter = ModelCounter::new(4, vec![vec![1, 2]], None).unwrap();
+    assert_eq!(counter.count(), BigUint::from(12u32));
+
+    let mut counter = ModelCounter::new(2, vec![vec![1, 2], vec![-1], vec![-2]], None).unwrap();
+    assert_eq!(counter.count(), BigUint::from(0u32));
+
+    // counts beyond u128
+    let mut counter = ModelCounter::new(200, vec![vec![1, 2, 3]], None).unwrap();
+    assert_eq!(counter.count(), (BigUint::from(1u32) << 200) - (BigUint::from(1u32) << 197));
+}
+
+#[test]
+fn count_projected_models() {
+    // 3 is 1 or 2, projected onto 3 both values are possible, onto 1 and 3 three assignments
+    let clauses = vec![vec![-3, 1, 2], vec![3, -1], vec![3, -2]];
+    let mut counter = ModelCounter::new(3, clauses.clone(), Some(vec![3])).unwrap();
+    assert_eq!(counter.count(), BigUint::from(2u32));
+    let mut counter = ModelCounter::new(3, clauses.clone(), Some(vec![1, 3])).unwrap();
+    assert_eq!(counter.count(), BigUint::from(3u32));
+
+    // projection variables outside the formula are errors
+    for variable in [0, 4, -1] {
+        assert!(ModelCounter::new(3, clauses.clone(), Some(vec![variable])).is_err());
+    }
+}
+
+#[test]
+fn count_weighted_models() {
+    // the models of 1 or 2 with w(1) = 1/2, w(-1) = 1/2, w(2) = 1/4, w(-2) = 3/4
+    let weights = vec![
+        (1, parse_rational("0.5").unwrap()),
+        (-1, parse_rational("1/2").unwrap()),
+        (2, parse_rational("25e-2").unwrap()),
+        (-2, parse_rational("0.75").unwrap()),
+    ];
+    let mut counter = ModelCounter::new(2, vec![vec![1, 2]], None).unwrap().with_weights(weights).unwrap();
+    assert_eq!(counter.count(), BigRational::new(5.into(), 8.into()));
+
+    let weights = vec![(1, 0.5f64), (-1, 0.5), (2, 0.25), (-2, 0.75)];
+    let mut counter = ModelCounter::new(2, vec![vec![1, 2]], None).unwrap().with_weights(weights).unwrap();
+    assert!((counter.count() - 0.625).abs() < 1e-12);
+
+    // weights of literals outside the formula are errors
+    for literal in [0, 3, -3] {
+        assert!(ModelCounter::new(2, vec![vec![1, 2]], None).unwrap().with_weights(vec![(literal, 0.5f64)]).is_err());
+    }
+    let path = std::env::temp_dir().join("dpll_count_test_weights.cnf");
+    for (line, valid) in [("c p weight -2 0.5 0", true), ("c p weight 3 0.5 0", false), ("c p weight x 0.5 0", false)] {
+        std::fs::write(&path, format!("p cnf 2 1\n{}\n1 2 0\n", line)).unwrap();
+        assert_eq!(read_weights(&path, false, 2).is_ok(), valid, "{}", line);
+    }
+}
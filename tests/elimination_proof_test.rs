@@ -0,0 +1,56 @@
+mod common;
+
+use common::assert_proof_verifies;
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::proof::{Proof, ProofFormat};
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+const FILES: [&str; 2] = ["data/inputs/test/unsat/count7_2.cnf", "data/inputs/test/unsat/stone_pyramid4_2.cnf"];
+
+fn eliminated(cnf: &PathBuf) -> Formula {
+    let mut formula = Formula::from_file(cnf).unwrap();
+    formula.heuristic_type = HeuristicType::VSIDS;
+    formula.preprocess = PreprocessConfig { elimination: true, ..PreprocessConfig::default() };
+    formula
+}
+
+#[test]
+fn elimination_proofs_are_verified() {
+    for (i, file) in FILES.iter().enumerate() {
+        let cnf = PathBuf::from(file);
+        for (format, name) in [(ProofFormat::Text, "drat"), (ProofFormat::Lrat, "lrat")] {
+            let path = std::env::temp_dir().join(format!("dpll_elimination_proof_test_{}_{}", i, name));
+            let mut formula = eliminated(&cnf);
+            formula.set_proof(Proof::create(&path, format).unwrap());
+            dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+            formula.take_proof();
+            assert_eq!(formula.result(), FormulaResultType::Unsatisfiable, "{}", file);
+            assert_proof_verifies(&cnf, &path, format);
+        }
+    }
+}
+
+#[test]
+fn elimination_cores_are_unsatisfiable() {
+    for file in FILES {
+        let cnf = PathBuf::from(file);
+        let mut formula = eliminated(&cnf);
+        formula.enable_unsat_core();
+        dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+        assert_eq!(formula.result(), FormulaResultType::Unsatisfiable, "{}", file);
+
+        // the core is made of the clauses of the input file, not of the resolvents
+        let original = Formula::from_file(&cnf).unwrap();
+        let mut core_formula = Formula::new(original.num_variables());
+        core_formula.heuristic_type = HeuristicType::VSIDS;
+        for id in formula.unsat_core().unwrap().unwrap() {
+            core_formula.add_original_clause(original.clauses()[id - 1].literals().to_vec());
+        }
+        dpll(&mut core_formula, Arc::new(AtomicBool::new(false)));
+        assert_eq!(core_formula.result(), FormulaResultType::Unsatisfiable, "{}", file);
+    }
+}
@@ -0,0 +1,36 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn eliminating_formula(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig { elimination: true, ..PreprocessConfig::default() };
+    formula
+}
+
+#[test]
+fn eliminated_variables_are_reconstructed() {
+    // 2 and 3 are defined by 1 and 4, after their elimination only clauses over 1 and 4 are left
+    let clauses = vec![vec![-2, 1], vec![2, -1], vec![-3, 2, 4], vec![3, -2], vec![3, -4], vec![-1, 3], vec![-4, -1], vec![1, 4]];
+    let mut formula = eliminating_formula(4, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let remaining: Vec<&[i16]> = formula.clauses().iter().filter(|clause| !clause.deleted).map(|clause| clause.literals()).collect();
+    assert!(remaining.len() < clauses.len());
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+}
+
+#[test]
+fn elimination_derives_the_empty_clause() {
+    // every variable can be eliminated, the last resolvent is empty
+    let clauses = vec![vec![1, 2], vec![-1, 2], vec![1, -2], vec![-1, -2, 3], vec![-3]];
+    let mut formula = eliminating_formula(3, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+}
@@ -0,0 +1,54 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::enumerate::ModelEnumerator;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use dpll::utils::read_projection;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn formula() -> Formula {
+    // 1 or 2, 3 is free
+    let mut formula = Formula::new(3);
+    formula.add_original_clause(vec![1, 2]);
+    formula.add_original_clause(vec![-3, 3]);
+    formula
+}
+
+#[test]
+fn enumerate_all_models() {
+    let mut formula = formula();
+    let mut models: Vec<Vec<i16>> =
+        ModelEnumerator::new(&mut formula, None, Arc::new(AtomicBool::new(false))).unwrap().collect();
+    models.sort();
+    assert_eq!(models.len(), 6);
+    models.dedup();
+    assert_eq!(models.len(), 6);
+
+    // the blocking clauses are removed with the enumerator
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+}
+
+#[test]
+fn enumerate_projected_models() {
+    let mut formula = formula();
+    let mut models: Vec<Vec<i16>> =
+        ModelEnumerator::new(&mut formula, Some(vec![1, 2]), Arc::new(AtomicBool::new(false))).unwrap().collect();
+    models.sort();
+    assert_eq!(models, vec![vec![-1, 2], vec![1, -2], vec![1, 2]]);
+
+    let models = ModelEnumerator::new(&mut formula, Some(vec![3]), Arc::new(AtomicBool::new(false))).unwrap().take(1);
+    assert_eq!(models.count(), 1);
+}
+
+#[test]
+fn invalid_projections_are_errors() {
+    let mut formula = formula();
+    assert!(ModelEnumerator::new(&mut formula, Some(vec![4]), Arc::new(AtomicBool::new(false))).is_err());
+    assert!(ModelEnumerator::new(&mut formula, Some(vec![-1]), Arc::new(AtomicBool::new(false))).is_err());
+
+    let path = std::env::temp_dir().join("dpll_enumerate_test_projection.cnf");
+    for (line, valid) in [("c p show 1 3 0", true), ("c p show 1 4 0", false), ("c p show 1 x 0", false)] {
+        std::fs::write(&path, format!("p cnf 3 1\n{}\n1 2 0\n", line)).unwrap();
+        assert_eq!(read_projection(&path, 3).is_ok(), valid, "{}", line);
+    }
+}
@@ -0,0 +1,36 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn substituting_formula(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig { substitution: true, ..PreprocessConfig::default() };
+    formula
+}
+
+#[test]
+fn equivalent_literals_are_substituted() {
+    // 1, 2 and -3 are equivalent, 1 is their representative
+    let clauses = vec![vec![1, -2], vec![-1, 2], vec![2, 3], vec![-2, -3], vec![2, -3, 4], vec![-4, 3, 5], vec![-5, -1]];
+    let mut formula = substituting_formula(5, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let remaining: Vec<&[i16]> = formula.clauses().iter().filter(|clause| !clause.deleted).map(|clause| clause.literals()).collect();
+    assert!(remaining.iter().all(|clause| clause.iter().all(|lit| lit.abs() != 2 && lit.abs() != 3)));
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+}
+
+#[test]
+fn a_literal_equivalent_to_its_negation_is_unsatisfiable() {
+    // 1 is equivalent to 2 and to -2
+    let clauses = vec![vec![1, -2], vec![-1, 2], vec![1, 2], vec![-1, -2], vec![1, 2, 3]];
+    let mut formula = substituting_formula(3, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+}
@@ -0,0 +1,65 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::gates::GateType;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn formula(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula
+}
+
+#[test]
+fn gates_are_detected() {
+    let clauses = vec![
+        // 3 = 1 ∧ 2
+        vec![-3, 1],
+        vec![-3, 2],
+        vec![3, -1, -2],
+        // 4 = 1 ⊕ 2
+        vec![-4, 1, 2],
+        vec![-4, -1, -2],
+        vec![4, -1, 2],
+        vec![4, 1, -2],
+        // 5 = 3 ? 4 : 1
+        vec![-5, -3, 4],
+        vec![-5, 3, 1],
+        vec![5, -3, -4],
+        vec![5, 3, -1],
+    ];
+    let gates = formula(5, &clauses).gates();
+    let gate = |output: i16| gates.iter().find(|gate| gate.output.abs() == output).map(|gate| gate.gate_type);
+    assert_eq!(gate(3), Some(GateType::And));
+    assert_eq!(gate(4), Some(GateType::Xor));
+    assert_eq!(gate(5), Some(GateType::Ite));
+    let xor = gates.iter().find(|gate| gate.output.abs() == 4).unwrap();
+    assert_eq!(xor.output, 4);
+    assert_eq!(xor.inputs, vec![1, 2]);
+}
+
+#[test]
+fn gates_allow_more_eliminations() {
+    // 5 = 1 ∧ 2 is used by three clauses, the resolvents of [5, 3] and [5, 4] with [-5, 3, 4] are
+    // not needed, which leaves 5 instead of 7 resolvents for its 6 clauses
+    let mut clauses = vec![vec![-5, 1], vec![-5, 2], vec![5, -1, -2], vec![5, 3], vec![5, 4], vec![-5, 3, 4]];
+    // the other variables have too many occurrences to be eliminated
+    for i in 1..=4 {
+        clauses.extend([vec![i, 6, 7], vec![i, -6, 8], vec![i, 7, -8], vec![i, -7, 6], vec![i, -6, -8]]);
+    }
+    let mut remaining = Vec::new();
+    for gates in [false, true] {
+        let mut formula = formula(8, &clauses);
+        formula.preprocess = PreprocessConfig { elimination: true, occurrence_limit: 6, gates, ..PreprocessConfig::default() };
+        dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+        let model = formula.model().unwrap();
+        assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+        remaining.push(formula.clauses().iter().filter(|clause| !clause.deleted && clause.literals().iter().any(|lit| lit.abs() == 5)).count());
+        assert_eq!(formula.statistics().gate_eliminations, usize::from(gates));
+    }
+    assert_eq!(remaining, vec![6, 0]);
+}
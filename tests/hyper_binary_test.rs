@@ -0,0 +1,52 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn solve(num_variables: usize, clauses: &[Vec<i16>], preprocess: PreprocessConfig) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = preprocess;
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+    formula
+}
+
+fn has_clause(formula: &Formula, literals: &[i16]) -> bool {
+    formula.clauses().iter().any(|clause| !clause.deleted && clause.literals() == literals)
+}
+
+#[test]
+fn probes_add_hyper_binary_resolvents() {
+    // 1 implies 2 and 3, which imply 4 together
+    let clauses = vec![vec![-1, 2], vec![-1, 3], vec![-2, -3, 4], vec![1, 5], vec![-4, 5, 6]];
+    let formula = solve(6, &clauses, PreprocessConfig { probing: true, hyper_binary: true, ..PreprocessConfig::default() });
+    assert!(has_clause(&formula, &[-1, 4]));
+    assert!(formula.statistics().hyper_binary_resolvents > 0);
+}
+
+#[test]
+fn transitive_binary_clauses_are_removed() {
+    // [-1, 3] follows from [-1, 2] and [-2, 3], five pigeons in five holes cause the conflicts
+    let mut clauses = vec![vec![-1, 2], vec![-2, 3], vec![-1, 3], vec![1, 3, 4]];
+    let variable = |pigeon: i16, hole: i16| 5 + 5 * pigeon + hole;
+    for pigeon in 0..5 {
+        clauses.push((0..5).map(|hole| variable(pigeon, hole)).collect());
+    }
+    for hole in 0..5 {
+        for first in 0..5 {
+            for second in first + 1..5 {
+                clauses.push(vec![-variable(first, hole), -variable(second, hole)]);
+            }
+        }
+    }
+    let preprocess = PreprocessConfig { transitive_reduction: true, inprocess_interval: 1, ..PreprocessConfig::default() };
+    let formula = solve(29, &clauses, preprocess);
+    assert!(!has_clause(&formula, &[-1, 3]));
+    assert!(formula.statistics().transitive_reductions > 0);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::assert_proof_verifies;
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::proof::{Proof, ProofFormat};
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn solve(formula: &mut Formula) -> FormulaResultType {
+    dpll(formula, Arc::new(AtomicBool::new(false)));
+    formula.result()
+}
+
+fn satisfies(formula: &Formula, clauses: &[Vec<i16>]) -> bool {
+    let model = formula.model().unwrap();
+    clauses
+        .iter()
+        .all(|clause| clause.iter().any(|lit| model.contains(lit)))
+}
+
+#[test]
+fn blocking_clauses_enumerate_all_models() {
+    let mut formula = Formula::new(3);
+    let mut clauses = vec![vec![1, 2], vec![-1, 3]];
+    for clause in &clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    let mut models = 0;
+    while solve(&mut formula) == FormulaResultType::Satisfiable {
+        assert!(satisfies(&formula, &clauses));
+        let blocking: Vec<i16> = formula.model().unwrap().iter().map(|lit| -lit).collect();
+        clauses.push(blocking.clone());
+        formula.add_original_clause(blocking);
+        models += 1;
+    }
+    assert_eq!(models, 4);
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+}
+
+#[test]
+fn new_variables_after_solve() {
+    let mut formula = Formula::from_file(&PathBuf::from("data/simple.txt")).unwrap();
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    let fresh = formula.new_variable();
+    assert_eq!(fresh, 8);
+    formula.add_original_clause(vec![fresh]);
+    formula.add_original_clause(vec![-fresh, -1]);
+    formula.add_original_clause(vec![9, -fresh]);
+    assert_eq!(formula.num_variables(), 9);
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(model.contains(&8) && model.contains(&-1) && model.contains(&9));
+    formula.add_original_clause(vec![-9]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+}
+
+#[test]
+fn pop_removes_scope_clauses() {
+    let mut formula = Formula::new(3);
+    formula.add_original_clause(vec![1, 2]);
+    formula.add_original_clause(vec![-1, 3]);
+    formula.push();
+    formula.add_original_clause(vec![-2]);
+    formula.add_original_clause(vec![-3]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+    assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+    assert!(formula.pop());
+    assert!(!formula.pop());
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    formula.push();
+    formula.add_original_clause(vec![-2]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(model.contains(&1) && model.contains(&-2) && model.contains(&3));
+    formula.push();
+    formula.add_original_clause(vec![-3]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+    assert!(formula.pop());
+    assert_eq!(formula.scope_depth(), 1);
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+}
+
+#[test]
+fn activation_literals_are_hidden_from_the_caller() {
+    let mut formula = Formula::new(3);
+    formula.add_original_clause(vec![1, 2]);
+    formula.push();
+    // 4 is a new variable of the caller, not the activation literal of the scope
+    formula.add_original_clause(vec![4]);
+    formula.add_original_clause(vec![-4, -1]);
+    assert_eq!(formula.num_variables(), 4);
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert_eq!(model.len(), 4);
+    assert!(model.contains(&4) && model.contains(&-1) && model.contains(&2));
+    assert!(formula.pop());
+    formula.set_assumptions(vec![-4, 1]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Satisfiable);
+    assert_eq!(formula.model().unwrap().len(), 4);
+    formula.add_original_clause(vec![-1]);
+    assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+    assert_eq!(formula.failed_assumptions(), vec![1]);
+}
+
+#[test]
+fn scope_proofs_are_verified() {
+    let cnf = PathBuf::from("data/inputs/test/unsat/hole5.cnf");
+    for (format, name) in [(ProofFormat::Text, "drat"), (ProofFormat::Lrat, "lrat")] {
+        let path = std::env::temp_dir().join(format!("dpll_incremental_test_{}", name));
+        let mut formula = Formula::from_file(&cnf).unwrap();
+        formula.set_proof(Proof::create(&path, format).unwrap());
+        // the clauses of the scope contradict each other, so the learned clause depends on them
+        formula.push();
+        for clause in [vec![1, 2], vec![1, -2], vec![-1, 2], vec![-1, -2]] {
+            formula.add_original_clause(clause);
+        }
+        assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+        assert!(formula.pop());
+        assert_eq!(solve(&mut formula), FormulaResultType::Unsatisfiable);
+        formula.take_proof();
+        assert_proof_verifies(&cnf, &path, format);
+    }
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::{assert_model_satisfies, solve_with};
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use std::path::PathBuf;
+
+// the search restarts from the root level for the probing every 5 conflicts
+fn assert_satisfiable_with_probing(path: &str) {
+    let original = Formula::from_file(&PathBuf::from(path)).unwrap();
+    for heuristic in [HeuristicType::None, HeuristicType::VSIDS] {
+        let preprocess = PreprocessConfig { probing: true, inprocess_interval: 5, ..PreprocessConfig::default() };
+        let formula = solve_with(path, heuristic, preprocess);
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable, "{} {:?}", path, heuristic);
+        assert_model_satisfies(&original, &formula.model().unwrap());
+    }
+}
+
+#[test]
+fn probing_restarts_find_models_of_pigeon_holes() {
+    assert_satisfiable_with_probing("data/inputs/test/sat/hole5_5.cnf");
+}
+
+#[test]
+fn probing_restarts_find_models_of_aim_instances() {
+    assert_satisfiable_with_probing("data/inputs/sat/aim-50-2_0-yes1-1.cnf");
+    assert_satisfiable_with_probing("data/inputs/sat/aim-100-6_0-yes1-1.cnf");
+}
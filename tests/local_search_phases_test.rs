@@ -0,0 +1,42 @@
+mod common;
+
+use common::assert_model_satisfies;
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::local_search::{LocalSearchConfig, LocalSearchPhases};
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+// a local search run after every conflict
+fn solve_with_phases(path: &PathBuf) -> Formula {
+    let mut formula = Formula::from_file(path).unwrap();
+    formula.local_search_phases = Some(LocalSearchPhases {
+        interval: 1,
+        flips: 100,
+        config: LocalSearchConfig::default(),
+    });
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula
+}
+
+fn files(directory: &str, names: &[&str]) -> Vec<PathBuf> {
+    names.iter().map(|name| PathBuf::from(directory).join(name)).collect()
+}
+
+#[test]
+fn local_search_phases_find_models() {
+    for path in files("data/inputs/test/sat", &["hole5_5.cnf", "kcolor.cnf", "matching6_4.cnf", "parity6.cnf", "rand6.cnf"]) {
+        let formula = solve_with_phases(&path);
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable, "{:?}", path);
+        assert_model_satisfies(&Formula::from_file(&path).unwrap(), &formula.model().unwrap());
+    }
+}
+
+#[test]
+fn local_search_phases_keep_unsatisfiable_results() {
+    for path in files("data/inputs/test/unsat", &["count7_2.cnf", "hole5.cnf", "parity5.cnf", "rand3.cnf", "tseitin.cnf"]) {
+        let formula = solve_with_phases(&path);
+        assert_eq!(formula.result(), FormulaResultType::Unsatisfiable, "{:?}", path);
+    }
+}
@@ -0,0 +1,30 @@
+use dpll::dpll::local_search::{local_search, LocalSearchConfig, LocalSearchType};
+use dpll::dpll::schemas::{Formula, FormulaResultType, Value};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+#[test]
+fn local_search_finds_a_model() {
+    let clauses = vec![vec![1, 2], vec![-1, 3], vec![-2, -3], vec![2, 3, 4], vec![-4, -1]];
+    for algorithm in [LocalSearchType::WalkSat, LocalSearchType::ProbSat] {
+        let mut formula = Formula::new(4);
+        for clause in &clauses {
+            formula.add_original_clause(clause.clone());
+        }
+        let config = LocalSearchConfig { algorithm, ..LocalSearchConfig::default() };
+        local_search(&mut formula, config, Arc::new(AtomicBool::new(false)));
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+        assert!(clauses.iter().all(|clause| clause.iter().any(|lit| formula.literal_value(*lit) == Value::True)));
+    }
+}
+
+#[test]
+fn local_search_gives_up_on_unsatisfiable_formulas() {
+    let mut formula = Formula::new(2);
+    for clause in [vec![1, 2], vec![-1, 2], vec![1, -2], vec![-1, -2]] {
+        formula.add_original_clause(clause);
+    }
+    let config = LocalSearchConfig { restart_flips: 100, max_tries: Some(2), ..LocalSearchConfig::default() };
+    local_search(&mut formula, config, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unknown);
+}
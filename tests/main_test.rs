@@ -1,5 +1,29 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn main() {
     print!("Hello, world!");
     assert!(true);
 }
+
+#[test]
+fn unsat_core_is_unsatisfiable() {
+    let mut formula = Formula::from_file(&PathBuf::from("data/inputs/test/unsat/hole5.cnf")).unwrap();
+    formula.enable_unsat_core();
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+    let core = formula.unsat_core().unwrap().unwrap();
+    assert!(!core.is_empty() && core.len() < formula.clauses().len());
+
+    let mut core_formula = Formula::new(formula.num_variables());
+    for id in core {
+        core_formula.add_original_clause(formula.clauses()[id - 1].literals().to_vec());
+    }
+    dpll(&mut core_formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(core_formula.result(), FormulaResultType::Unsatisfiable);
+}
@@ -0,0 +1,31 @@
+use dpll::dpll::groups::GroupedClauses;
+use dpll::dpll::mcs::McsEnumerator;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn write_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dpll_mcs_test_{}", name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn all_mcs_of_groups() {
+    // 1 is hard, the groups want 1 false, 2 true and 2 false
+    let path = write_file("groups.gcnf", "p gcnf 2 4 3\n{0} 1 0\n{1} -1 0\n{2} 2 0\n{3} -2 0\n");
+    let clauses = GroupedClauses::from_file(&path).unwrap();
+    let mut enumerator = McsEnumerator::new(&clauses, Arc::new(AtomicBool::new(false)));
+    let mut mcses: Vec<Vec<usize>> = enumerator.by_ref().collect();
+    mcses.sort();
+    assert_eq!(mcses, vec![vec![1, 2], vec![1, 3]]);
+    assert!(enumerator.is_complete());
+}
+
+#[test]
+fn satisfiable_formula_has_empty_mcs() {
+    let path = write_file("satisfiable.cnf", "p cnf 2 2\n1 2 0\n-1 2 0\n");
+    let clauses = GroupedClauses::from_file(&path).unwrap();
+    let mcses: Vec<Vec<usize>> = McsEnumerator::new(&clauses, Arc::new(AtomicBool::new(false))).collect();
+    assert_eq!(mcses, vec![Vec::<usize>::new()]);
+}
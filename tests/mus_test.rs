@@ -0,0 +1,38 @@
+use dpll::dpll::groups::GroupedClauses;
+use dpll::dpll::mus::MusExtractor;
+use dpll::dpll::schemas::FormulaResultType;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn write_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dpll_mus_test_{}", name));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn mus_of_groups() {
+    let path = write_file(
+        "groups.gcnf",
+        "p gcnf 3 7 4\n{0} 1 2 0\n{1} -1 0\n{1} 3 0\n{2} -2 0\n{3} -2 -3 0\n{4} 2 -3 0\n{4} -3 0\n",
+    );
+    let clauses = GroupedClauses::from_file(&path).unwrap();
+    assert_eq!(MusExtractor::new(&clauses, Arc::new(AtomicBool::new(false))).find_mus(), Ok(Some(vec![1, 2])));
+    // after the timeout no group is known to be removable
+    let stopped = Arc::new(AtomicBool::new(true));
+    assert_eq!(MusExtractor::new(&clauses, stopped).find_mus(), Err(FormulaResultType::Timeout));
+}
+
+#[test]
+fn mus_of_clauses() {
+    let path = write_file("clauses.cnf", "p cnf 2 5\n1 2 0\n-1 2 0\n1 0\n-2 0\n1 -2 0\n");
+    let clauses = GroupedClauses::from_file(&path).unwrap();
+    let mus = MusExtractor::new(&clauses, Arc::new(AtomicBool::new(false))).find_mus().unwrap().unwrap();
+    // both -2 with one of the clauses that imply 2 are minimal
+    assert!(mus == vec![1, 2, 4] || mus == vec![2, 3, 4]);
+
+    let path = write_file("satisfiable.cnf", "p cnf 2 2\n1 2 0\n-1 2 0\n");
+    let clauses = GroupedClauses::from_file(&path).unwrap();
+    assert_eq!(MusExtractor::new(&clauses, Arc::new(AtomicBool::new(false))).find_mus(), Ok(None));
+}
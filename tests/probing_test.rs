@@ -0,0 +1,38 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn probe(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig { probing: true, ..PreprocessConfig::default() };
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+    formula
+}
+
+fn has_unit(formula: &Formula, unit: i16) -> bool {
+    formula.clauses().iter().any(|clause| !clause.deleted && clause.literals() == [unit])
+}
+
+#[test]
+fn failed_literals_become_units() {
+    // 1 implies 2 and 3, which imply 4, and 4 contradicts 1
+    let clauses = vec![vec![-1, 2], vec![-1, 3], vec![-2, -3, 4], vec![-4, -1], vec![1, 5], vec![-5, 4]];
+    let formula = probe(5, &clauses);
+    assert!(has_unit(&formula, -1));
+}
+
+#[test]
+fn literals_implied_by_both_polarities_become_units() {
+    // 1 implies 2 directly, -1 implies 2 over 3
+    let clauses = vec![vec![-1, 2], vec![1, 3], vec![-3, 2], vec![-2, 4, 5]];
+    let formula = probe(5, &clauses);
+    assert!(has_unit(&formula, 2));
+}
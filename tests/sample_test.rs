@@ -0,0 +1,32 @@
+use dpll::dpll::sample::Sampler;
+
+fn clauses() -> Vec<Vec<i16>> {
+    // 147 models
+    vec![vec![1, 2, 3], vec![-4, 5], vec![6, -7, 8]]
+}
+
+#[test]
+fn samples_are_models_and_reproducible() {
+    let samples: Vec<Vec<i16>> = Sampler::new(8, clauses(), None, 3).take(20).collect();
+    assert_eq!(samples.len(), 20);
+    for sample in &samples {
+        assert!(clauses().iter().all(|clause| clause.iter().any(|lit| sample.contains(lit))));
+    }
+    let again: Vec<Vec<i16>> = Sampler::new(8, clauses(), None, 3).take(20).collect();
+    assert_eq!(samples, again);
+}
+
+#[test]
+fn sample_few_models_and_unsatisfiable_formula() {
+    let mut samples: Vec<Vec<i16>> = Sampler::new(2, vec![vec![1, 2]], None, 1).take(100).collect();
+    samples.sort();
+    samples.dedup();
+    assert_eq!(samples, vec![vec![-1, 2], vec![1, -2], vec![1, 2]]);
+    let mut sampler = Sampler::new(2, vec![vec![1, 2]], None, 1);
+    assert!(sampler.next().is_some());
+    assert!(!sampler.is_unsatisfiable());
+
+    let mut sampler = Sampler::new(1, vec![vec![1], vec![-1]], None, 1);
+    assert_eq!(sampler.next(), None);
+    assert!(sampler.is_unsatisfiable());
+}
@@ -0,0 +1,60 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{ClauseType, Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn subsuming_formula(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig { subsumption: true, ..PreprocessConfig::default() };
+    formula
+}
+
+fn remaining(formula: &Formula) -> Vec<Vec<i16>> {
+    formula
+        .clauses()
+        .iter()
+        .filter(|clause| !clause.deleted)
+        .map(|clause| {
+            let mut literals = clause.literals().to_vec();
+            literals.sort_unstable();
+            literals
+        })
+        .collect()
+}
+
+#[test]
+fn subsumed_clauses_are_removed_and_strengthened() {
+    // [1, 2] subsumes [1, 2, 3] and strengthens [-1, 2, 4] to [2, 4], which subsumes [2, 4, 5]
+    let clauses = vec![vec![1, 2], vec![1, 2, 3], vec![-1, 2, 4], vec![2, 4, 5], vec![-2, -4, 5]];
+    let mut formula = subsuming_formula(5, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    assert_eq!(remaining(&formula), vec![vec![1, 2], vec![-4, -2, 5], vec![2, 4]]);
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+}
+
+#[test]
+fn strengthening_derives_the_empty_clause() {
+    // the units strengthen [-1, 2] to [-1] and then to the empty clause
+    let clauses = vec![vec![-1, 2], vec![-2], vec![1]];
+    let mut formula = subsuming_formula(2, &clauses);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+
+    // the strengthened clauses are derived, so the core goes back to the clauses they come from
+    let path = std::env::temp_dir().join("dpll_subsumption_test_core.cnf");
+    std::fs::write(&path, "p cnf 4 5\n-1 2 3 0\n-2 3 0\n1 3 0\n-3 4 0\n-3 -4 0\n").unwrap();
+    let mut formula = Formula::from_file(&path).unwrap();
+    formula.preprocess = PreprocessConfig { subsumption: true, ..PreprocessConfig::default() };
+    formula.enable_unsat_core();
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+    let derived = &formula.clauses()[5..];
+    assert!(!derived.is_empty() && derived.iter().all(|clause| clause.clause_type == ClauseType::Derived));
+    assert_eq!(formula.unsat_core().unwrap().unwrap(), vec![1, 2, 3, 4, 5]);
+}
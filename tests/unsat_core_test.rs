@@ -0,0 +1,49 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+fn core(formula: &mut Formula) -> Vec<usize> {
+    formula.enable_unsat_core();
+    dpll(formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+    formula.unsat_core().unwrap().unwrap()
+}
+
+#[test]
+fn core_only_contains_clauses_of_the_input_file() {
+    let path = PathBuf::from("data/inputs/test/unsat/hole5.cnf");
+    let original = Formula::from_file(&path).unwrap();
+    let length = original.clauses().len();
+
+    // the clauses added after reading the file have no number in it
+    let mut formula = Formula::from_file(&path).unwrap();
+    formula.add_original_clause(vec![1, 2]);
+    formula.add_original_clause(vec![-1, 31]);
+    assert!(core(&mut formula).iter().all(|id| *id <= length));
+
+    // the resolvents are followed back to the clauses they are resolved from
+    let mut formula = Formula::from_file(&path).unwrap();
+    formula.heuristic_type = HeuristicType::VSIDS;
+    formula.preprocess = PreprocessConfig { elimination: true, subsumption: true, ..PreprocessConfig::default() };
+    let ids = core(&mut formula);
+    assert!(!ids.is_empty() && ids.iter().all(|id| *id <= length));
+
+    let mut core_formula = Formula::new(original.num_variables());
+    for id in ids {
+        core_formula.add_original_clause(original.clauses()[id - 1].literals().to_vec());
+    }
+    dpll(&mut core_formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(core_formula.result(), FormulaResultType::Unsatisfiable);
+}
+
+#[test]
+fn core_of_clauses_added_later_is_empty() {
+    // only the clauses added after reading the file contradict each other
+    let mut formula = Formula::from_file(&PathBuf::from("data/inputs/test/sat/hole5_5.cnf")).unwrap();
+    formula.add_original_clause(vec![1]);
+    formula.add_original_clause(vec![-1]);
+    assert!(core(&mut formula).is_empty());
+}
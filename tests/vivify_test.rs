@@ -0,0 +1,71 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::preprocess::PreprocessConfig;
+use dpll::dpll::schemas::{Formula, FormulaResultType, HeuristicType};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+// every pigeon is in a hole and no hole has two pigeons, the variable of pigeon p in hole h is
+// offset + p * holes + h + 1
+fn pigeons(offset: i16, pigeons: i16, holes: i16) -> Vec<Vec<i16>> {
+    let variable = |pigeon: i16, hole: i16| offset + pigeon * holes + hole + 1;
+    let mut clauses: Vec<Vec<i16>> = (0..pigeons).map(|pigeon| (0..holes).map(|hole| variable(pigeon, hole)).collect()).collect();
+    for hole in 0..holes {
+        for first in 0..pigeons {
+            for second in first + 1..pigeons {
+                clauses.push(vec![-variable(first, hole), -variable(second, hole)]);
+            }
+        }
+    }
+    clauses
+}
+
+fn vivify(num_variables: usize, clauses: &[Vec<i16>]) -> Formula {
+    let mut formula = Formula::new(num_variables);
+    for clause in clauses {
+        formula.add_original_clause(clause.clone());
+    }
+    formula.preprocess = PreprocessConfig {
+        vivification: true,
+        vivify_original: true,
+        vivify_effort: 10_000,
+        inprocess_interval: 1,
+        ..PreprocessConfig::default()
+    };
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    formula
+}
+
+#[test]
+fn vivified_formulas_keep_their_result() {
+    let formula = vivify(20, &pigeons(0, 5, 4));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+}
+
+#[test]
+fn redundant_literals_are_removed() {
+    // -2 implies -1, so 1 is removed from [2, 1, 3], the pigeons cause the conflicts before
+    let mut clauses = vec![vec![-1, 2], vec![2, 1, 3], vec![-2, -3, 4]];
+    clauses.extend(pigeons(4, 5, 5));
+    let formula = vivify(29, &clauses);
+    assert_eq!(formula.result(), FormulaResultType::Satisfiable);
+    let model = formula.model().unwrap();
+    assert!(clauses.iter().all(|clause| clause.iter().any(|lit| model.contains(lit))));
+    assert!(formula.clauses().iter().any(|clause| !clause.deleted && clause.literals() == [2, 3]));
+}
+
+#[test]
+fn vivification_restarts_keep_satisfiable_formulas() {
+    let path = PathBuf::from("data/inputs/test/sat/hole5_5.cnf");
+    let original = Formula::from_file(&path).unwrap();
+    for heuristic in [HeuristicType::None, HeuristicType::VSIDS] {
+        let mut formula = Formula::from_file(&path).unwrap();
+        formula.heuristic_type = heuristic;
+        formula.preprocess =
+            PreprocessConfig { elimination: true, vivification: true, inprocess_interval: 5, ..PreprocessConfig::default() };
+        dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+        assert_eq!(formula.result(), FormulaResultType::Satisfiable, "{:?}", heuristic);
+        let model = formula.model().unwrap();
+        assert!(original.clauses().iter().all(|clause| clause.literals().iter().any(|lit| model.contains(lit))));
+    }
+}
@@ -0,0 +1,30 @@
+use dpll::dpll::dpll::dpll;
+use dpll::dpll::enumerate::ModelEnumerator;
+use dpll::dpll::schemas::{Formula, FormulaResultType};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+#[test]
+fn xor_constraints_are_propagated() {
+    // 1 xor 2 xor 3, 1 = 2 and 1 or 3 have the models 1 2 3 and -1 -2 3
+    let mut formula = Formula::new(3);
+    formula.add_xor_constraint(vec![1, 2, 3]);
+    formula.add_xor_constraint(vec![1, -2]);
+    formula.add_original_clause(vec![1, 3]);
+    let mut models: Vec<Vec<i16>> = ModelEnumerator::new(&mut formula, None, Arc::new(AtomicBool::new(false))).unwrap().collect();
+    models.sort();
+    assert_eq!(models, vec![vec![-1, -2, 3], vec![1, 2, 3]]);
+}
+
+#[test]
+fn contradicting_xor_constraints_are_unsatisfiable() {
+    // the sum of the first three constraints is 1 xor 4 = 1, which contradicts the last one
+    let mut formula = Formula::new(4);
+    formula.add_xor_constraint(vec![1, 2]);
+    formula.add_xor_constraint(vec![2, 3]);
+    formula.add_xor_constraint(vec![3, 4]);
+    formula.add_xor_constraint(vec![1, -4]);
+    formula.add_original_clause(vec![1, 2, 3, 4]);
+    dpll(&mut formula, Arc::new(AtomicBool::new(false)));
+    assert_eq!(formula.result(), FormulaResultType::Unsatisfiable);
+}